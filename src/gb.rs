@@ -7,10 +7,28 @@ pub mod rom;
 pub mod emu;
 pub mod bios;
 pub mod mbc;
+pub mod bus;
+pub mod disasm;
+pub mod debugger;
+pub mod gdbstub;
+pub mod trace;
+pub mod symbols;
+pub mod headless;
+pub mod mooneye;
+pub mod blargg;
+pub mod png;
+pub mod screenshot;
+pub mod reftest;
+pub mod recorder;
+pub mod movie;
+pub mod savestate;
+pub mod keybindings;
+pub mod pacer;
 
 pub mod constants;
 
 pub mod graphics;
 pub mod gbwindow;
-mod testcpu;
+pub mod testcpu;
 pub mod joypad;
+pub mod sgb;
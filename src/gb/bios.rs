@@ -3,9 +3,32 @@
 
 
 
-#[derive(Debug, Clone, Copy)]pub enum ColorMode {
+use crate::gb::rom::Rom;
+
+// which hardware runs, a CGB (Color), a DMG (Gray) or a DMG inside a Super Game Boy (Super)
+// a DMG cart on a CGB runs in compatibility mode, the boot rom sets that up through KEY0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]pub enum ColorMode {
     Color,
     Gray,
+    Super,
+}
+
+impl ColorMode {
+    // what the cart asks for, color if it knows about the CGB
+    // the SGB only runs when it's picked, its border makes the screen bigger
+    pub fn for_rom(rom: &Rom) -> Self {
+        if rom.is_cgb() { ColorMode::Color } else { ColorMode::Gray }
+    }
+
+    // --model cgb, --model dmg, --model sgb
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cgb" => Some(ColorMode::Color),
+            "dmg" => Some(ColorMode::Gray),
+            "sgb" => Some(ColorMode::Super),
+            _ => None,
+        }
+    }
 }
 
 pub struct Bios {
@@ -170,7 +193,8 @@ impl Bios {
             
             ],
             //https://github.com/Hacktix/Bootix
-            ColorMode::Gray => bios.data = vec![
+            // the sgb's own boot rom also sends the header to the snes, the games don't need that
+            ColorMode::Gray | ColorMode::Super => bios.data = vec![
                 0x31, 0xFE, 0xFF, 0xAF, 0x21, 0xFF, 0x9F, 0x32, 0xCB, 0x7C, 0x20, 0xFB, 0x21, 0x26, 0xFF, 0x0E, 
                 0x11, 0x3E, 0x80, 0x32, 0xE2, 0x0C, 0x3E, 0xF3, 0xE2, 0x32, 0x3E, 0x77, 0x77, 0x3E, 0xFC, 0xE0, 
                 0x47, 0x11, 0x04, 0x01, 0x21, 0x10, 0x80, 0x1A, 0xCD, 0x95, 0x00, 0xCD, 0x96, 0x00, 0x13, 0x7B, 
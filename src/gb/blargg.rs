@@ -0,0 +1,121 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::gb::emu::Emu;
+use crate::gb::headless::{Headless, panic_message};
+use crate::gb::mbc::OpSource;
+
+// runs blargg's test roms (cpu_instrs, instr_timing, mem_timing...) without a window
+// they print their results over serial, ending in "Passed" or "Failed"
+// the newer ones also keep them in cart ram: A000 is the status, 0x80 while running and 0 for a pass,
+// A001-A003 is the DE B0 61 signature and the text starts at A004
+// https://github.com/retrio/gb-test-roms
+
+// cpu_instrs takes close to a minute on hardware
+pub const DEFAULT_TIMEOUT_MCYCLES: u64 = 120 * 1_048_576;
+
+const SIGNATURE: [u8; 3] = [0xDE, 0xB0, 0x61];
+const STATUS_RUNNING: u8 = 0x80;
+// once a result shows up, run a bit longer so the rest of the line makes it out
+const TAIL_MCYCLES: u64 = 200_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlarggResult {
+    Pass(String),
+    Fail(String), // what the rom printed
+    Timeout(String), // whatever it printed before giving up
+    Crash(String),
+}
+
+impl BlarggResult {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, BlarggResult::Pass(_))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            BlarggResult::Pass(_) => String::from("pass"),
+            BlarggResult::Fail(text) => format!("fail\n{}", text),
+            BlarggResult::Timeout(text) if text.is_empty() => String::from("timeout, nothing printed"),
+            BlarggResult::Timeout(text) => format!("timeout\n{}", text),
+            BlarggResult::Crash(msg) => format!("crash: {}", msg),
+        }
+    }
+}
+
+pub fn get_serial_text(emu: &Emu) -> String {
+    String::from_utf8_lossy(&emu.mbc.serial_out).trim().to_string()
+}
+
+// cart ram results, None until the signature is there and the test is done
+pub fn get_memory_result(emu: &Emu) -> Option<(u8, String)> {
+    let read = |address: u16| emu.mbc.read(address, OpSource::Debugger);
+    let signature = [read(0xA001), read(0xA002), read(0xA003)];
+    let status = read(0xA000);
+    if signature != SIGNATURE || status == STATUS_RUNNING {
+        return None;
+    }
+    let mut text = Vec::new();
+    let mut address = 0xA004;
+    while address < 0xC000 && read(address) != 0 {
+        text.push(read(address));
+        address += 1;
+    }
+    Some((status, String::from_utf8_lossy(&text).trim().to_string()))
+}
+
+fn is_serial_done(emu: &Emu) -> bool {
+    let text = get_serial_text(emu);
+    text.contains("Passed") || text.contains("Failed")
+}
+
+pub fn run_blargg_rom(rom_file: &str, timeout_mcycles: u64) -> BlarggResult {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut headless = Headless::new(rom_file);
+        // the serial text only needs another look when a byte came in
+        let mut serial_len = 0;
+        let is_done = headless.run_until(timeout_mcycles, |emu| {
+            if emu.mbc.serial_out.len() != serial_len {
+                serial_len = emu.mbc.serial_out.len();
+                if is_serial_done(emu) {
+                    return true;
+                }
+            }
+            get_memory_result(emu).is_some()
+        });
+        if !is_done {
+            return BlarggResult::Timeout(get_serial_text(&headless.emu));
+        }
+        headless.run_until(TAIL_MCYCLES, |_| false);
+
+        if let Some((status, text)) = get_memory_result(&headless.emu) {
+            return if status == 0 {
+                BlarggResult::Pass(text)
+            } else {
+                BlarggResult::Fail(format!("{}\nstatus 0x{:02X}", text, status))
+            };
+        }
+        let text = get_serial_text(&headless.emu);
+        if text.contains("Failed") {
+            BlarggResult::Fail(text)
+        } else {
+            BlarggResult::Pass(text)
+        }
+    }));
+    result.unwrap_or_else(|e| BlarggResult::Crash(panic_message(e)))
+}
+
+// gbemu blargg <rom...>
+pub fn run_blargg_command(args: &[String]) {
+    if args.is_empty() {
+        println!("usage: gbemu blargg <rom...>");
+        return;
+    }
+    let mut results = Vec::new();
+    for rom in args {
+        results.push((rom, run_blargg_rom(rom, DEFAULT_TIMEOUT_MCYCLES)));
+    }
+    println!();
+    for (rom, result) in &results {
+        println!("{}: {}", rom, result.describe());
+    }
+}
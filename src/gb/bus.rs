@@ -0,0 +1,95 @@
+use std::ops::{Deref, DerefMut};
+use crate::gb::mbc::*;
+use crate::gb::debugger::WatchKind;
+
+// the bus sits between the cpu and the rest of the system
+// every memory access the cpu makes takes one mcycle, so the timer, dma and ppu are stepped
+// forward by one mcycle right before the access lands. cycles where the cpu is busy
+// without touching memory (eg. the extra cycle in CALL) go through tick_internal()
+pub struct Bus<'a> {
+    pub mbc: &'a mut Mbc,
+    on_mcycle: &'a mut dyn FnMut(&mut Mbc),
+    pub mcycles: u64, // mcycles stepped since the bus was created or reset
+    pub activity: Option<Vec<BusCycle>>, // every mcycle in order when set, the json cpu tests check it
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BusCycle {
+    Read(u16, u8),
+    Write(u16, u8),
+    Internal,
+}
+
+impl<'a> Bus<'a> {
+    pub fn new(mbc: &'a mut Mbc, on_mcycle: &'a mut dyn FnMut(&mut Mbc)) -> Self {
+        Bus {
+            mbc,
+            on_mcycle,
+            mcycles: 0,
+            activity: None,
+        }
+    }
+
+    pub fn tick_mcycle(&mut self) {
+        self.mcycles += 1;
+        self.mbc.tick_timer();
+        if self.mbc.dma_active || self.mbc.dma_start_delay > 0 {
+            self.mbc.tick_dma();
+        }
+        if self.mbc.hdma_bytes_left > 0 {
+            self.mbc.tick_hdma();
+        }
+        // in double speed the cpu, timer, dma and hdma run twice as fast as the ppu
+        if self.mbc.hw_reg.is_double_speed() {
+            self.mbc.is_ppu_mcycle_skipped = !self.mbc.is_ppu_mcycle_skipped;
+            if self.mbc.is_ppu_mcycle_skipped {
+                return;
+            }
+        }
+        (self.on_mcycle)(self.mbc);
+    }
+
+    pub fn tick_internal(&mut self) {
+        self.tick_mcycle();
+        if let Some(activity) = self.activity.as_mut() {
+            activity.push(BusCycle::Internal);
+        }
+    }
+
+    pub fn read(&mut self, address: u16, op_src: OpSource) -> u8 {
+        self.tick_mcycle();
+        let value = self.mbc.read(address, op_src);
+        if let Some(activity) = self.activity.as_mut() {
+            activity.push(BusCycle::Read(address, value));
+        }
+        if !self.mbc.watchpoints.is_empty() {
+            self.mbc.check_watchpoints(address, value, WatchKind::Read);
+        }
+        value
+    }
+
+    pub fn write(&mut self, address: u16, byte: u8, op_src: OpSource) {
+        self.tick_mcycle();
+        if let Some(activity) = self.activity.as_mut() {
+            activity.push(BusCycle::Write(address, byte));
+        }
+        if !self.mbc.watchpoints.is_empty() {
+            self.mbc.check_watchpoints(address, byte, WatchKind::Write);
+        }
+        self.mbc.write(address, byte, op_src);
+    }
+}
+
+impl<'a> Deref for Bus<'a> {
+    type Target = Mbc;
+
+    fn deref(&self) -> &Mbc {
+        self.mbc
+    }
+}
+
+impl<'a> DerefMut for Bus<'a> {
+    fn deref_mut(&mut self) -> &mut Mbc {
+        self.mbc
+    }
+}
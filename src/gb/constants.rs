@@ -0,0 +1,4 @@
+use crate::gb::cpu::MAX_T_CYCLE_PER_FRAME;
+
+// a frame is 70224 tcycles, 4 to an mcycle
+pub const MCYCLES_PER_FRAME: u64 = MAX_T_CYCLE_PER_FRAME / 4;
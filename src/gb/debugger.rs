@@ -0,0 +1,515 @@
+use std::io::BufRead;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use crate::gb::cpu::Cpu;
+use crate::gb::mbc::*;
+use crate::gb::disasm::*;
+use crate::gb::symbols::Symbols;
+use crate::gb::emu::EmuCommand;
+
+// built in debugger, driven by commands typed into the terminal while the game window is open
+// the emu thread asks it before and after every cpu step whether to keep running
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    Execute,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub start: u16,
+    pub end: u16, // inclusive
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Watchpoint {
+    pub fn matches(&self, address: u16, kind: WatchKind) -> bool {
+        if address < self.start || address > self.end {
+            return false;
+        }
+        match kind {
+            WatchKind::Read => self.read,
+            WatchKind::Write => self.write,
+            WatchKind::Execute => self.execute,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WatchHit {
+    pub address: u16,
+    pub value: u8,
+    pub kind: WatchKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub register: String,
+    pub op: CompareOp,
+    pub value: u16,
+}
+
+impl Condition {
+    pub fn is_met(&self, cpu: &Cpu) -> bool {
+        let Some(reg) = get_register(cpu, &self.register) else { return false; };
+        match self.op {
+            CompareOp::Eq => reg == self.value,
+            CompareOp::Ne => reg != self.value,
+            CompareOp::Lt => reg < self.value,
+            CompareOp::Gt => reg > self.value,
+            CompareOp::Le => reg <= self.value,
+            CompareOp::Ge => reg >= self.value,
+        }
+    }
+}
+
+// address None is a condition that is checked before every instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub bank: Option<u8>,
+    pub address: Option<u16>,
+    pub condition: Option<Condition>,
+}
+
+impl Breakpoint {
+    pub fn is_hit(&self, cpu: &Cpu, mbc: &Mbc) -> bool {
+        let pc = cpu.registers.get_pc();
+        if let Some(address) = self.address {
+            if pc != address {
+                return false;
+            }
+            if let Some(bank) = self.bank {
+                if mbc.get_rom_bank(pc) != bank {
+                    return false;
+                }
+            }
+        }
+        match &self.condition {
+            Some(condition) => condition.is_met(cpu),
+            None => self.address.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugMode {
+    Running,
+    Paused,
+    StepInto,
+    StepOver { return_address: u16, sp: u16 },
+    StepOut { sp: u16 },
+    RunToVBlank,
+}
+
+pub struct Debugger {
+    pub mode: DebugMode,
+    pub breakpoints: Vec<Breakpoint>,
+    commands: Option<Receiver<String>>,
+    skip_breakpoint_at: Option<u16>, // resuming from a breakpoint shouldn't hit it again straight away
+    step_opcode: u8,
+    step_ly: u8,
+    pub symbols: Option<Arc<Symbols>>,
+    pub emu_commands: Vec<EmuCommand>, // console commands for the emu itself, it picks them up every tick
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger {
+            mode: DebugMode::Running,
+            breakpoints: Vec::new(),
+            commands: None,
+            skip_breakpoint_at: None,
+            step_opcode: 0,
+            step_ly: 0,
+            symbols: None,
+            emu_commands: Vec::new(),
+        }
+    }
+
+    // reads commands from stdin on its own thread so the windows keep running
+    pub fn attach_console(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let stdin = std::io::stdin();
+            for line in stdin.lock().lines() {
+                let Ok(line) = line else { break; };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        self.commands = Some(rx);
+        println!("debugger console attached, type help for commands");
+    }
+
+    pub fn is_console_attached(&self) -> bool {
+        self.commands.is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.mode == DebugMode::Paused
+    }
+
+    pub fn pause(&mut self, reason: &str, cpu: &Cpu, mbc: &Mbc) {
+        self.mode = DebugMode::Paused;
+        println!("paused: {}", reason);
+        print_state(cpu, mbc, self.symbols.as_deref());
+    }
+
+    fn resume(&mut self, mode: DebugMode, cpu: &Cpu) {
+        self.skip_breakpoint_at = Some(cpu.registers.get_pc());
+        self.mode = mode;
+        // nothing runs while the emu is paused either
+        self.emu_commands.push(EmuCommand::SetPaused(false));
+    }
+
+    // true if the cpu should run an instruction now
+    pub fn before_step(&mut self, cpu: &Cpu, mbc: &mut Mbc) -> bool {
+        self.poll_commands(cpu, mbc);
+        if self.mode == DebugMode::Paused {
+            // nothing to run, wait on the console instead of spinning
+            let command = match &self.commands {
+                Some(commands) => commands.recv_timeout(Duration::from_millis(10)).ok(),
+                None => {
+                    thread::sleep(Duration::from_millis(10));
+                    None
+                }
+            };
+            if let Some(command) = command {
+                self.run_command(&command, cpu, mbc);
+            }
+            return false;
+        }
+
+        let pc = cpu.registers.get_pc();
+        if self.skip_breakpoint_at != Some(pc) {
+            if let Some(idx) = self.breakpoints.iter().position(|bp| bp.is_hit(cpu, mbc)) {
+                self.pause(&format!("breakpoint {}", idx), cpu, mbc);
+                return false;
+            }
+            if mbc.watchpoints.iter().any(|wp| wp.matches(pc, WatchKind::Execute)) {
+                self.pause(&format!("execute watchpoint at {:04X}", pc), cpu, mbc);
+                return false;
+            }
+        }
+        self.skip_breakpoint_at = None;
+
+        self.step_opcode = mbc.read(pc, OpSource::Debugger);
+        self.step_ly = mbc.hw_reg.ly;
+        true
+    }
+
+    pub fn after_step(&mut self, cpu: &Cpu, mbc: &mut Mbc) {
+        if let Some(hit) = mbc.watch_hit.take() {
+            let reason = format!("{:?} watchpoint at {:04X}, value {:02X}", hit.kind, hit.address, hit.value);
+            self.pause(&reason, cpu, mbc);
+            return;
+        }
+        let pc = cpu.registers.get_pc();
+        let sp = cpu.registers.get_sp();
+        match self.mode {
+            DebugMode::StepInto => {
+                self.pause("step", cpu, mbc);
+            },
+            DebugMode::StepOver { return_address, sp: call_sp } => {
+                if pc == return_address && sp >= call_sp {
+                    self.pause("step over", cpu, mbc);
+                }
+            },
+            DebugMode::StepOut { sp: start_sp } => {
+                // RET, RETI and the conditional RETs, only counts once the stack unwinds past where we started
+                let is_ret = matches!(self.step_opcode, 0xC9 | 0xD9 | 0xC0 | 0xC8 | 0xD0 | 0xD8);
+                if is_ret && sp > start_sp {
+                    self.pause("step out", cpu, mbc);
+                }
+            },
+            DebugMode::RunToVBlank => {
+                if self.step_ly != 144 && mbc.hw_reg.ly == 144 {
+                    self.pause("vblank", cpu, mbc);
+                }
+            },
+            DebugMode::Running | DebugMode::Paused => {},
+        }
+    }
+
+    pub fn poll_commands(&mut self, cpu: &Cpu, mbc: &mut Mbc) {
+        let Some(commands) = &self.commands else { return; };
+        let pending: Vec<String> = commands.try_iter().collect();
+        for command in pending {
+            self.run_command(&command, cpu, mbc);
+        }
+    }
+
+    pub fn run_command(&mut self, line: &str, cpu: &Cpu, mbc: &mut Mbc) {
+        let args: Vec<&str> = line.split_whitespace().collect();
+        let Some(&command) = args.first() else { return; };
+        let pc = cpu.registers.get_pc();
+        match command {
+            "c" | "continue" => self.resume(DebugMode::Running, cpu),
+            "p" | "pause" => self.pause("pause", cpu, mbc),
+            "s" | "step" => self.resume(DebugMode::StepInto, cpu),
+            "n" | "next" => {
+                let line = &disassemble_mbc(mbc, pc, 1, None)[0];
+                let opcode = line.bytes[0];
+                let is_call = matches!(opcode, 0xCD | 0xC4 | 0xCC | 0xD4 | 0xDC) || (opcode & 0xC7) == 0xC7;
+                if is_call {
+                    let return_address = pc.wrapping_add(line.size());
+                    self.resume(DebugMode::StepOver { return_address, sp: cpu.registers.get_sp() }, cpu);
+                } else {
+                    self.resume(DebugMode::StepInto, cpu);
+                }
+            },
+            "out" | "finish" => self.resume(DebugMode::StepOut { sp: cpu.registers.get_sp() }, cpu),
+            "v" | "vblank" => self.resume(DebugMode::RunToVBlank, cpu),
+            "b" | "break" => match self.parse_breakpoint(&args[1..]) {
+                Some(bp) => {
+                    println!("breakpoint {} set", self.breakpoints.len());
+                    self.breakpoints.push(bp);
+                },
+                None => println!("usage: break [bank:]address|label [if reg op value] | break if reg op value"),
+            },
+            "w" | "watch" => match self.parse_watchpoint(&args[1..]) {
+                Some(wp) => {
+                    println!("watchpoint {} set", mbc.watchpoints.len());
+                    mbc.watchpoints.push(wp);
+                },
+                None => println!("usage: watch address[-end] [r|w|x|rw|rwx]"),
+            },
+            "del" | "delete" => {
+                match args.get(1).and_then(|idx| idx.parse::<usize>().ok()) {
+                    Some(idx) if idx < self.breakpoints.len() => { self.breakpoints.remove(idx); },
+                    _ => println!("no such breakpoint"),
+                }
+            },
+            "delw" | "unwatch" => {
+                match args.get(1).and_then(|idx| idx.parse::<usize>().ok()) {
+                    Some(idx) if idx < mbc.watchpoints.len() => { mbc.watchpoints.remove(idx); },
+                    _ => println!("no such watchpoint"),
+                }
+            },
+            "clear" => {
+                self.breakpoints.clear();
+                mbc.watchpoints.clear();
+            },
+            "l" | "list" | "info" => {
+                for (idx, bp) in self.breakpoints.iter().enumerate() {
+                    let mut text = match (bp.bank, bp.address) {
+                        (Some(bank), Some(address)) => format!("{:02X}:{:04X}", bank, address),
+                        (None, Some(address)) => format!("{:04X}", address),
+                        _ => String::from("any"),
+                    };
+                    if let (Some(address), Some(symbols)) = (bp.address, self.symbols.as_ref()) {
+                        if let Some(label) = symbols.get_label(bp.bank.unwrap_or(0), address) {
+                            text = format!("{} {}", text, label);
+                        }
+                    }
+                    if let Some(cond) = &bp.condition {
+                        text = format!("{} if {} {:?} {:X}", text, cond.register, cond.op, cond.value);
+                    }
+                    println!("breakpoint {}: {}", idx, text);
+                }
+                for (idx, wp) in mbc.watchpoints.iter().enumerate() {
+                    let kinds: String = [(wp.read, 'r'), (wp.write, 'w'), (wp.execute, 'x')]
+                        .iter().filter(|(on, _)| *on).map(|(_, c)| *c).collect();
+                    println!("watchpoint {}: {:04X}-{:04X} {}", idx, wp.start, wp.end, kinds);
+                }
+            },
+            "frame" => {
+                let frames = args.get(1).and_then(|n| n.parse::<u64>().ok()).unwrap_or(1);
+                self.skip_breakpoint_at = Some(pc);
+                self.mode = DebugMode::Running;
+                self.emu_commands.push(EmuCommand::AdvanceFrames(frames));
+            },
+            "reset" => match args.get(1) {
+                Some(&"hard") => self.emu_commands.push(EmuCommand::HardReset),
+                None | Some(&"soft") => self.emu_commands.push(EmuCommand::SoftReset),
+                _ => println!("usage: reset [soft|hard]"),
+            },
+            "load" => match line.split_once(' ') {
+                Some((_, file)) if !file.trim().is_empty() => self.emu_commands.push(EmuCommand::LoadRom(String::from(file.trim()))),
+                _ => println!("usage: load FILE"),
+            },
+            "r" | "regs" => print_state(cpu, mbc, self.symbols.as_deref()),
+            "x" | "mem" => {
+                let Some(start) = args.get(1).and_then(|a| self.parse_address(a)) else {
+                    println!("usage: mem address [length]");
+                    return;
+                };
+                let len = args.get(2).and_then(|a| parse_hex(a)).unwrap_or(0x40);
+                for row in (0..len).step_by(16) {
+                    let row_start = start.wrapping_add(row);
+                    let bytes: Vec<String> = (0..16.min(len - row))
+                        .map(|i| format!("{:02X}", mbc.read(row_start.wrapping_add(i), OpSource::Debugger)))
+                        .collect();
+                    println!("{:04X}: {}", row_start, bytes.join(" "));
+                }
+            },
+            "d" | "dis" => {
+                let start = args.get(1).and_then(|a| self.parse_address(a)).unwrap_or(pc);
+                let count = args.get(2).and_then(|a| a.parse::<usize>().ok()).unwrap_or(10);
+                for line in disassemble_mbc(mbc, start, count, self.symbols.as_deref()) {
+                    println!("{}", line.to_line());
+                }
+            },
+            "h" | "help" => print_help(),
+            _ => println!("unknown command {}, type help for commands", command),
+        }
+    }
+
+    // labels win over hex so a label like cafe still works
+    fn parse_location(&self, text: &str) -> Option<(Option<u8>, u16)> {
+        if let Some((bank, address)) = self.symbols.as_ref().and_then(|symbols| symbols.get_address(text)) {
+            return Some((Some(bank), address));
+        }
+        if text.contains(':') {
+            let (bank, address) = parse_bank_address(text)?;
+            return Some((Some(bank), address));
+        }
+        Some((None, parse_hex(text)?))
+    }
+
+    fn parse_address(&self, text: &str) -> Option<u16> {
+        self.parse_location(text).map(|(_, address)| address)
+    }
+
+    fn parse_breakpoint(&self, args: &[&str]) -> Option<Breakpoint> {
+        match args {
+            ["if", cond @ ..] => Some(Breakpoint { bank: None, address: None, condition: Some(parse_condition(cond)?) }),
+            [location, rest @ ..] => {
+                // a bare address in the switchable area matches any bank
+                let (bank, address) = self.parse_location(location)?;
+                let condition = match rest {
+                    [] => None,
+                    ["if", cond @ ..] => Some(parse_condition(cond)?),
+                    _ => return None,
+                };
+                Some(Breakpoint { bank, address: Some(address), condition })
+            },
+            [] => None,
+        }
+    }
+
+    fn parse_watchpoint(&self, args: &[&str]) -> Option<Watchpoint> {
+        let range = args.first()?;
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (self.parse_address(start)?, self.parse_address(end)?),
+            None => (self.parse_address(range)?, self.parse_address(range)?),
+        };
+        let kinds = args.get(1).copied().unwrap_or("w");
+        if kinds.is_empty() || !kinds.chars().all(|c| matches!(c, 'r' | 'w' | 'x')) {
+            return None;
+        }
+        Some(Watchpoint {
+            start: start.min(end),
+            end: start.max(end),
+            read: kinds.contains('r'),
+            write: kinds.contains('w'),
+            execute: kinds.contains('x'),
+        })
+    }
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Debugger::new()
+    }
+}
+
+// the names get_register knows, conditions can only use these
+pub const REGISTER_NAMES: [&str; 14] = ["a", "f", "b", "c", "d", "e", "h", "l", "af", "bc", "de", "hl", "sp", "pc"];
+
+pub fn get_register(cpu: &Cpu, name: &str) -> Option<u16> {
+    let regs = &cpu.registers;
+    let val = match name.to_ascii_lowercase().as_str() {
+        "a" => regs.get_a() as u16,
+        "f" => regs.get_f() as u16,
+        "b" => regs.get_b() as u16,
+        "c" => regs.get_c() as u16,
+        "d" => regs.get_d() as u16,
+        "e" => regs.get_e() as u16,
+        "h" => regs.get_h() as u16,
+        "l" => regs.get_l() as u16,
+        "af" => regs.get_af(),
+        "bc" => regs.get_bc(),
+        "de" => regs.get_de(),
+        "hl" => regs.get_hl(),
+        "sp" => regs.get_sp(),
+        "pc" => regs.get_pc(),
+        _ => return None,
+    };
+    Some(val)
+}
+
+fn parse_hex(text: &str) -> Option<u16> {
+    let text = text.trim_start_matches('$').trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(text, 16).ok()
+}
+
+// a == 3, hl != c000
+pub fn parse_condition(args: &[&str]) -> Option<Condition> {
+    if args.len() != 3 {
+        return None;
+    }
+    let op = match args[1] {
+        "==" => CompareOp::Eq,
+        "!=" => CompareOp::Ne,
+        "<" => CompareOp::Lt,
+        ">" => CompareOp::Gt,
+        "<=" => CompareOp::Le,
+        ">=" => CompareOp::Ge,
+        _ => return None,
+    };
+    let register = args[0].to_ascii_lowercase();
+    if !REGISTER_NAMES.contains(&register.as_str()) {
+        return None;
+    }
+    Some(Condition { register, op, value: parse_hex(args[2])? })
+}
+
+pub fn print_state(cpu: &Cpu, mbc: &Mbc, symbols: Option<&Symbols>) {
+    let regs = &cpu.registers;
+    println!(
+        "AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X} PC={:04X} IME={} LY={:02X}",
+        regs.get_af(), regs.get_bc(), regs.get_de(), regs.get_hl(), regs.get_sp(), regs.get_pc(), cpu.ime as u8, mbc.hw_reg.ly
+    );
+    let line = &disassemble_mbc(mbc, regs.get_pc(), 1, symbols)[0];
+    println!("{}", line.to_line());
+}
+
+fn print_help() {
+    println!("numbers are hex, addresses can be bank:address or a label from the .sym file");
+    println!("  c, continue             run until something breaks");
+    println!("  p, pause                stop where the cpu is");
+    println!("  s, step                 run one instruction");
+    println!("  n, next                 step over calls and rst");
+    println!("  out, finish             run until the current function returns");
+    println!("  v, vblank               run until LY reaches 144");
+    println!("  frame [N]               run N frames (default 1) and pause the emu");
+    println!("  reset [soft|hard]       soft keeps ram, hard power cycles everything but the cartridge");
+    println!("  load FILE               swap in another rom, the current one's save ram is written first");
+    println!("  b, break ADDR [if R OP V]   break at ADDR, optionally only if register R OP V");
+    println!("  b, break if R OP V      break on any instruction where register R OP V, OP is == != < > <= >=");
+    println!("  w, watch ADDR[-END] [rwx]   break on read, write or execute in a range, default w");
+    println!("  del N, delw N, clear    remove breakpoint N, watchpoint N, or everything");
+    println!("  l, list                 show breakpoints and watchpoints");
+    println!("  r, regs                 show registers and the next instruction");
+    println!("  x, mem ADDR [LEN]       dump memory");
+    println!("  d, dis [ADDR] [COUNT]   disassemble, defaults to pc");
+}
@@ -0,0 +1,201 @@
+use crate::gb::instructions::{Instruction, INSTRUCTIONS, CB_INSTRUCTIONS};
+use crate::gb::mbc::*;
+use crate::gb::rom::Rom;
+use crate::gb::symbols::Symbols;
+
+// turns bytes back into sm83 mnemonics
+// the names come from the same tables the cpu decodes with, operands are filled in here
+// output looks like rgbds, eg. LD A,(FF00+C) or JR NZ,$-5
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disassembled {
+    pub address: u16,
+    pub bank: u8,
+    pub bytes: Vec<u8>,
+    pub text: String,
+    pub target: Option<u16>, // where a jump, call or rst goes if it's known without running
+    pub label: Option<String>, // label at this address, filled in by apply_symbols
+}
+
+impl Disassembled {
+    pub fn size(&self) -> u16 {
+        self.bytes.len() as u16
+    }
+
+    // 01:4000  21 00 C0   LD HL,$C000
+    // with a label it gets its own line first
+    pub fn to_line(&self) -> String {
+        let bytes: Vec<String> = self.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        let line = format!("{:02X}:{:04X}  {:<9}  {}", self.bank, self.address, bytes.join(" "), self.text);
+        match &self.label {
+            Some(label) => format!("{}:\n{}", label, line),
+            None => line,
+        }
+    }
+
+    // swaps the target address for its label, target_bank is what's mapped at 4000-7FFF
+    pub fn apply_symbols(&mut self, symbols: &Symbols, target_bank: u8) {
+        self.label = symbols.get_label(self.bank, self.address).map(String::from);
+        let Some(target) = self.target else { return; };
+        let bank = if (0x4000..=0x7FFF).contains(&target) { target_bank } else { 0 };
+        let Some(label) = symbols.get_label(bank, target) else { return; };
+        // the target is always the last operand
+        let operand_start = self.text.rfind([',', ' ']).map(|idx| idx + 1).unwrap_or(self.text.len());
+        self.text = format!("{}{}", &self.text[..operand_start], label);
+    }
+}
+
+// disassemble one instruction, read is called with cpu addresses starting at address
+pub fn disassemble(read: &dyn Fn(u16) -> u8, address: u16, bank: u8) -> Disassembled {
+    let opcode = read(address);
+    let (inst, mut bytes): (Instruction, Vec<u8>) = if opcode == 0xCB {
+        let cb_opcode = read(address.wrapping_add(1));
+        (CB_INSTRUCTIONS[cb_opcode as usize], vec![opcode, cb_opcode])
+    } else {
+        (INSTRUCTIONS[opcode as usize], vec![opcode])
+    };
+    while bytes.len() < inst.size as usize {
+        bytes.push(read(address.wrapping_add(bytes.len() as u16)));
+    }
+
+    // illegal opcodes are most likely data
+    if inst.name.starts_with("ILLEGAL") {
+        return Disassembled { address, bank, bytes, text: format!("DB ${:02X}", opcode), target: None, label: None };
+    }
+
+    let mut target = None;
+    let mut tokens = inst.name.split_whitespace();
+    let mnemonic = tokens.next().unwrap_or("");
+    let operands: Vec<String> = tokens.map(|token| {
+        match token {
+            "D8" => format!("${:02X}", bytes[1]),
+            "D16" | "A16" | "(A16)" => {
+                let word = u16::from_le_bytes([bytes[1], bytes[2]]);
+                if mnemonic == "JP" || mnemonic == "CALL" {
+                    target = Some(word);
+                }
+                if token == "(A16)" { format!("(${:04X})", word) } else { format!("${:04X}", word) }
+            },
+            "(A8)" => format!("(FF00+${:02X})", bytes[1]),
+            "(C)" => String::from("(FF00+C)"),
+            "R8" => {
+                // relative to the start of the jr, the way it's written in asm
+                let offset = bytes[1] as i8 as i16 + 2;
+                target = Some(address.wrapping_add(offset as u16));
+                if offset < 0 { format!("$-{}", -offset) } else { format!("$+{}", offset) }
+            },
+            "S8" => format!("{}", bytes[1] as i8),
+            "SP+S8" => {
+                let offset = bytes[1] as i8;
+                if offset < 0 { format!("SP-{}", -(offset as i16)) } else { format!("SP+{}", offset) }
+            },
+            _ if mnemonic == "RST" => {
+                let vector = u16::from_str_radix(token.trim_end_matches('H'), 16).unwrap_or(0);
+                target = Some(vector);
+                format!("${:02X}", vector)
+            },
+            _ => String::from(token),
+        }
+    }).collect();
+
+    let text = if operands.is_empty() || mnemonic == "STOP" {
+        String::from(mnemonic)
+    } else {
+        format!("{} {}", mnemonic, operands.join(","))
+    };
+    Disassembled { address, bank, bytes, text, target, label: None }
+}
+
+// disassemble count instructions in a row
+pub fn disassemble_range(read: &dyn Fn(u16) -> u8, address: u16, bank: u8, count: usize) -> Vec<Disassembled> {
+    let mut lines = Vec::with_capacity(count);
+    let mut current = address;
+    for _ in 0..count {
+        let line = disassemble(read, current, bank);
+        current = current.wrapping_add(line.size());
+        lines.push(line);
+    }
+    lines
+}
+
+// what the cpu would see right now, using whatever bank is mapped in
+pub fn disassemble_mbc(mbc: &Mbc, address: u16, count: usize, symbols: Option<&Symbols>) -> Vec<Disassembled> {
+    let read = |addr: u16| mbc.read(addr, OpSource::Debugger);
+    let mut lines = disassemble_range(&read, address, mbc.get_rom_bank(address), count);
+    if let Some(symbols) = symbols {
+        for line in lines.iter_mut() {
+            line.apply_symbols(symbols, mbc.get_rom_bank(0x4000));
+        }
+    }
+    lines
+}
+
+// reads straight out of a rom file, bank picks what sits at 0x4000-0x7FFF
+pub fn read_rom_bank(rom: &Rom, bank: u8, address: u16) -> u8 {
+    // anything past the end of the file or outside rom reads as open bus
+    if address >= 0x8000 {
+        return 0xFF;
+    }
+    let offset = if address < ROM_BANK_SIZE {
+        address as usize
+    } else {
+        (bank as usize) * (ROM_BANK_SIZE as usize) + (address as usize - ROM_BANK_SIZE as usize)
+    };
+    *rom.data.get(offset).unwrap_or(&0xFF)
+}
+
+pub fn disassemble_rom(rom: &Rom, bank: u8, address: u16, count: usize, symbols: Option<&Symbols>) -> Vec<Disassembled> {
+    let read = |addr: u16| read_rom_bank(rom, bank, addr);
+    let start_bank = if address < ROM_BANK_SIZE { 0 } else { bank };
+    let mut lines = disassemble_range(&read, address, start_bank, count);
+    if let Some(symbols) = symbols {
+        for line in lines.iter_mut() {
+            line.apply_symbols(symbols, bank.max(1));
+        }
+    }
+    lines
+}
+
+// parses 0150, $0150, 0x0150 or bank:address like 01:4000
+pub fn parse_bank_address(text: &str) -> Option<(u8, u16)> {
+    let parse_hex = |s: &str| {
+        let s = s.trim_start_matches('$').trim_start_matches("0x").trim_start_matches("0X");
+        u32::from_str_radix(s, 16).ok()
+    };
+    if let Some((bank, address)) = text.split_once(':') {
+        let bank = parse_hex(bank)?;
+        let address = parse_hex(address)?;
+        if bank > 0xFF || address > 0xFFFF { return None; }
+        Some((bank as u8, address as u16))
+    } else {
+        let address = parse_hex(text)?;
+        if address > 0xFFFF { return None; }
+        let bank = if (0x4000..=0x7FFF).contains(&address) { 1 } else { 0 };
+        Some((bank, address as u16))
+    }
+}
+
+// gbemu disasm <rom> [bank:address|label] [count]
+pub fn run_disasm_command(args: &[String]) {
+    let Some(file) = args.first() else {
+        println!("usage: gbemu disasm <rom> [bank:address|label] [count]");
+        return;
+    };
+    let symbols = Symbols::load_for_rom(file);
+    let (bank, address) = match args.get(1) {
+        Some(start) => match parse_bank_address(start).or_else(|| symbols.as_ref()?.get_address(start)) {
+            Some(start) => start,
+            None => {
+                println!("unable to parse start address {}", start);
+                return;
+            }
+        },
+        None => (0, 0x100),
+    };
+    let count = args.get(2).and_then(|c| c.parse::<usize>().ok()).unwrap_or(64);
+
+    let rom = Rom::new(file.as_str());
+    for line in disassemble_rom(&rom, bank, address, count, symbols.as_ref()) {
+        println!("{}", line.to_line());
+    }
+}
@@ -2,57 +2,245 @@ use crate::gb::cpu::*;
 use crate::gb::rom::*;
 use crate::gb::bios::*;
 use crate::gb::mbc::*;
+use crate::gb::ram::Ram;
+use crate::gb::bus::Bus;
 use crate::gb::graphics::ppu::*;
-use crate::gb::testcpu::*;
 use crate::gb::hwregisters::HardwareRegisters;
 use crate::gb::gbwindow::*;
-use crate::gb::joypad::Joypad;
+use crate::gb::joypad::Button;
+use crate::gb::debugger::Debugger;
+use crate::gb::gdbstub::GdbStub;
+use crate::gb::symbols::Symbols;
+use crate::gb::debugger::print_state;
+use crate::gb::constants::MCYCLES_PER_FRAME;
+use crate::gb::recorder::VideoRecorder;
+use crate::gb::screenshot::get_timestamp;
+use crate::gb::movie::{Movie, MovieStart, MovieWriter};
+use crate::gb::png::crc32;
+use crate::gb::savestate::{compress, decompress, State, StateReader, STATE_MAGIC, STATE_VERSION};
+use crate::gb::pacer::{FramePacer, Speed};
+use crate::gb::sgb::{Sgb, SGB_BUFFER_SIZE};
 
-use std::time::{Duration, Instant};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::{Sender, Receiver};
 use pixels::Pixels;
 
+// things the frontend needs to know about that aren't a frame being ready
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EmuEvent {
+    LockUp(LockUp),
+    Reset,
+}
+
+// things the frontend asks the emu thread to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmuCommand {
+    ToggleRecording,
+    SetButton(Button, bool), // held or released, takes effect at the end of the frame
+    TogglePause,
+    SetPaused(bool),
+    AdvanceFrames(u64), // runs that many frames and pauses
+    SoftReset,
+    HardReset,
+    SetFastForward(bool),
+    SetSpeed(Speed),
+    LoadRom(String),
+    SaveState, // to the rom's .state file
+    LoadState,
+    Shutdown, // save what needs saving, the frontend is about to exit
+}
+
+// where the joypad gets its buttons from each frame
+pub enum InputSource {
+    Live,
+    Recording(MovieWriter), // live, and written down
+    Playback(Movie, usize), // the movie and the next frame in it
+}
+
 pub struct Emu {
     pub cpu: Cpu,
     bios: Bios,
+    pub color_mode: Option<ColorMode>, // None picks it from each cart's header
     pub mbc: Box<Mbc>, // mbc includes rom and ram
     pub ppu: Ppu,
     // pub lcd: Lcd,
     pub debug: bool,
-    pub sec_mcycles: u64, // tracking max mcycles per sec
-    pub current_time: Instant,
-    pub is_cpu_test_enabled: bool,
-    pub is_cpu_tested: bool,
-    pub test_mbc: Box<Mbc>,
-    pub test_cpu: Cpu,
-    pub joypad: Arc<Mutex<Joypad>>
+    pub pacer: FramePacer,
+    pub speed: Speed, // when not fast forwarding
+    pub fast_forward_speed: Speed,
+    pub input: u8, // Button bits the frontend holds right now
+    pub input_source: InputSource,
+    pub is_paused: bool,
+    frames_to_advance: u64, // frames to run while paused
+    pub rom_file: Option<String>,
+    pub is_shut_down: bool,
+    pub is_fast_forward: bool, // runs at fast_forward_speed
+    pub event_tx: Option<Sender<EmuEvent>>,
+    is_lock_up_reported: bool,
+    pub debugger: Debugger,
+    pub gdb: Option<GdbStub>,
+    pub symbols: Option<Arc<Symbols>>,
+    pub command_rx: Option<Receiver<EmuCommand>>,
+    pub recorder: Option<VideoRecorder>,
+    frame_mcycles: u64, // since the last frame, frames still end every 17556 mcycles while the lcd is off
+    last_step_mcycles: u64, // at the ppu's rate, half the cpu's in double speed
+    pub sgb_buffer: Arc<Mutex<Vec<u8>>>, // 256x224 rgba, the game with its border, only drawn on a super game boy
 }
 
+// everything else on the emu is settings and frontend
+crate::impl_state!(Emu { cpu, mbc, ppu, frame_mcycles, last_step_mcycles });
+
 impl Emu {
-    pub fn new(color_mode: ColorMode, joypad: Arc<Mutex<Joypad>>, debug: bool) -> Self {
-        Emu {
+    pub fn new(color_mode: Option<ColorMode>, debug: bool) -> Self {
+        let mut emu = Emu {
             cpu: Cpu::new(),
             mbc: Box::new(Mbc::new()), // mbc has rom and ram
-            bios: Bios::new(color_mode), 
+            bios: Bios::new(color_mode.unwrap_or(ColorMode::Gray)),
+            color_mode,
             ppu: Ppu::new(),
             // lcd: Lcd::new(),
             debug,
-            sec_mcycles: 0, // tracking max mcycles per sec
-            current_time: Instant::now(),
-            is_cpu_tested: false,
-            is_cpu_test_enabled: true,
-            test_mbc: Box::new(Mbc::new()),
-            test_cpu: Cpu::new(),
-            joypad,
-        }
+            pacer: FramePacer::new(Speed::Times(1.0)),
+            speed: Speed::Times(1.0),
+            fast_forward_speed: Speed::Unthrottled,
+            input: 0,
+            input_source: InputSource::Live,
+            is_paused: false,
+            frames_to_advance: 0,
+            rom_file: None,
+            is_shut_down: false,
+            is_fast_forward: false,
+            event_tx: None,
+            is_lock_up_reported: false,
+            debugger: Debugger::new(),
+            gdb: None,
+            symbols: None,
+            command_rx: None,
+            recorder: None,
+            frame_mcycles: 0,
+            last_step_mcycles: 0,
+            sgb_buffer: Arc::new(Mutex::new(vec![0u8; SGB_BUFFER_SIZE])),
+        };
+        emu.load_bios();
+        emu
     }
 
     pub fn load_rom_file(&mut self, file: String) {
-        self.mbc.rom = Some(Rom::new(file.as_str()));
+        self.load_rom(&file).expect("Unable to load Rom file in Emu::load_rom_file()");
+    }
+
+    // swaps the cartridge, the old one's save goes to disk first and the new one starts from power-on
+    // nothing changes if the new rom can't be loaded
+    pub fn load_rom(&mut self, file: &str) -> std::io::Result<()> {
+        let rom = Rom::load(file)?;
+        if !Mbc::is_mapper_supported(rom.get_rom_type()) {
+            return Err(std::io::Error::other(format!("{:?} carts aren't supported yet", rom.get_rom_type())));
+        }
+        self.unload_rom();
+        let color_mode = self.color_mode.unwrap_or(ColorMode::for_rom(&rom));
+        if color_mode != self.bios.mode {
+            self.bios = Bios::new(color_mode);
+            self.load_bios();
+        }
+        self.mbc.is_cgb = color_mode == ColorMode::Color;
+        self.mbc.sgb = if color_mode == ColorMode::Super { Some(Sgb::new(rom.is_sgb())) } else { None };
+        let has_battery = rom.has_battery();
+        self.mbc.rom = Some(rom);
+        if has_battery {
+            let save_file = get_save_file(file);
+            if let Ok(data) = fs::read(&save_file) {
+                self.mbc.load_save_ram(&data);
+                println!("loaded save ram from {}", save_file);
+            }
+        }
+        self.rom_file = Some(String::from(file));
+        self.set_symbols(Symbols::load_for_rom(file));
+        self.reset(false);
+        let model = match color_mode {
+            ColorMode::Color => "cgb",
+            ColorMode::Gray => "dmg",
+            ColorMode::Super if self.mbc.sgb.as_ref().is_some_and(|sgb| sgb.is_enabled) => "sgb",
+            ColorMode::Super => "sgb, the cart doesn't use it",
+        };
+        println!("loaded rom {} on a {}", file, model);
+        Ok(())
+    }
+
+    pub fn unload_rom(&mut self) {
+        self.flush_save_ram();
+        self.mbc.eject();
+        self.rom_file = None;
+    }
+
+    // battery backed carts only, the rest have nothing to keep
+    pub fn flush_save_ram(&mut self) {
+        let (Some(data), Some(rom_file)) = (self.mbc.get_save_ram(), self.rom_file.as_ref()) else { return; };
+        let save_file = get_save_file(rom_file);
+        match fs::write(&save_file, data) {
+            Ok(()) => println!("wrote save ram to {}", save_file),
+            Err(e) => println!("unable to write save ram to {}: {}", save_file, e),
+        }
+    }
+
+    // one slot per rom, next to it like the .sav
+    pub fn save_state_file(&mut self) {
+        let Some(rom_file) = self.rom_file.as_ref() else { return; };
+        let state_file = get_state_file(rom_file);
+        match fs::write(&state_file, self.save_state()) {
+            Ok(()) => println!("saved state to {}", state_file),
+            Err(e) => println!("unable to save state to {}: {}", state_file, e),
+        }
+    }
+
+    pub fn load_state_file(&mut self) {
+        let Some(rom_file) = self.rom_file.as_ref() else { return; };
+        let state_file = get_state_file(rom_file);
+        let data = match fs::read(&state_file) {
+            Ok(data) => data,
+            Err(e) => {
+                println!("unable to read save state {}: {}", state_file, e);
+                return;
+            },
+        };
+        // a movie can't replay a jump it doesn't know about
+        if !matches!(self.input_source, InputSource::Live) {
+            println!("loading a state isn't part of input movies, stopping the movie");
+            self.stop_movie();
+        }
+        match self.load_state(&data) {
+            Ok(()) => {
+                println!("loaded state from {}", state_file);
+                self.pacer.restart();
+            },
+            Err(e) => println!("unable to load save state {}: {}", state_file, e),
+        }
+    }
+
+    // the emu thread stops ticking after this
+    pub fn shut_down(&mut self) {
+        self.flush_save_ram();
+        self.stop_recording();
+        self.stop_movie();
+        if let Some(tracer) = self.cpu.tracer.as_mut() {
+            tracer.flush();
+        }
+        self.is_shut_down = true;
+    }
+
+    // everything that prints addresses shares the same labels
+    pub fn set_symbols(&mut self, symbols: Option<Symbols>) {
+        self.symbols = symbols.map(Arc::new);
+        self.debugger.symbols = self.symbols.clone();
+        if let Some(tracer) = self.cpu.tracer.as_mut() {
+            tracer.symbols = self.symbols.clone();
+        }
     }
 
     pub fn load_bios(&mut self) {
+        self.mbc.boot_rom = Ram::new(0x00);
         self.mbc.boot_rom.load_bios_to_mem(&self.bios);
     }
 
@@ -60,133 +248,368 @@ impl Emu {
     //     self.ppu.load_all_tiles(&self.mbc);
     // }
 
-    pub fn test_cpu(&mut self) {
-        println!("TESTING CPU");
-        let all_cpu_tests = get_all_tests();
-        //println!("Got all tests, starting testing");
-        for test in &all_cpu_tests {
-            //println!("executing test {}", test.name);
-            // setup state
-                // registers
-            setup_initial_registers(&mut self.test_cpu.registers, &test.initial_test_state);
-            // ram
-            for ram_entry in &test.initial_test_state.test_ram {
-                self.test_mbc.write(ram_entry.0, ram_entry.1, OpSource::CPU);
+    // from the frontend's channel and the debugger console
+    pub fn handle_commands(&mut self) {
+        let mut commands: Vec<EmuCommand> = self.debugger.emu_commands.drain(..).collect();
+        if let Some(command_rx) = self.command_rx.as_ref() {
+            commands.extend(command_rx.try_iter());
+        }
+        for command in commands {
+            match command {
+                EmuCommand::ToggleRecording => self.toggle_recording(),
+                EmuCommand::SetButton(button, is_held) => self.set_button(button, is_held),
+                EmuCommand::TogglePause => self.set_paused(!self.is_paused),
+                EmuCommand::SetPaused(is_paused) => self.set_paused(is_paused),
+                EmuCommand::AdvanceFrames(frames) => self.advance_frames(frames),
+                EmuCommand::SoftReset => self.soft_reset(),
+                EmuCommand::HardReset => self.hard_reset(),
+                EmuCommand::SetFastForward(is_fast_forward) => {
+                    self.is_fast_forward = is_fast_forward;
+                    self.update_speed();
+                },
+                EmuCommand::SetSpeed(speed) => {
+                    self.speed = speed;
+                    self.update_speed();
+                },
+                EmuCommand::LoadRom(file) => {
+                    if let Err(e) = self.load_rom(&file) {
+                        println!("unable to load rom {}: {}", file, e);
+                    }
+                },
+                EmuCommand::SaveState => self.save_state_file(),
+                EmuCommand::LoadState => self.load_state_file(),
+                EmuCommand::Shutdown => self.shut_down(),
             }
-            // setup done
+        }
+    }
 
-            // execute cpu
-            self.test_cpu.tick(&mut self.test_mbc);
+    pub fn set_paused(&mut self, is_paused: bool) {
+        if is_paused != self.is_paused {
+            println!("{}", if is_paused { "paused" } else { "unpaused" });
+        }
+        self.is_paused = is_paused;
+        self.frames_to_advance = 0;
+        self.pacer.restart();
+    }
 
+    // pauses if it wasn't already, then runs exactly that many frames
+    pub fn advance_frames(&mut self, frames: u64) {
+        self.is_paused = true;
+        self.frames_to_advance += frames;
+        self.pacer.restart();
+    }
 
-            // check state
-                // registers
-                let failed_registers = compare_registers(&self.test_cpu.registers, &test.final_test_state);
-                if !failed_registers.is_empty() {
-                    println!("Failed registers:");
-                    for register in &failed_registers {
-                        println!("{:x?}", register);
-                    }
-                    panic!("CPU TEST FAILED - REGISTERS");
-                }
-            else {
-                //println!("all registers passed!");
-            }
-                // ram
-            // clean up registers and ram
-            for ram_entry in &test.initial_test_state.test_ram {
-                self.test_mbc.write(ram_entry.0, 0, OpSource::CPU);
-            }
-            // print result
-            // if result says all CPU tests passed, continue
-            // else print name, error, and panic
+    // false while paused, unless frames are being advanced
+    pub fn is_running(&self) -> bool {
+        !self.is_paused || self.frames_to_advance > 0
+    }
+
+    // what the reset pin does, cpu, ppu and io start over from the boot rom but ram keeps its contents
+    pub fn soft_reset(&mut self) {
+        println!("soft reset");
+        self.reset(true);
+    }
+
+    // power cycle, only the cartridge (rom and its ram) survives
+    pub fn hard_reset(&mut self) {
+        println!("hard reset");
+        self.reset(false);
+    }
+
+    fn reset(&mut self, keep_memory: bool) {
+        // a movie can't replay a reset it doesn't know about
+        if !matches!(self.input_source, InputSource::Live) {
+            println!("resets aren't part of input movies, stopping the movie");
+            self.stop_movie();
+        }
+        let tracer = self.cpu.tracer.take();
+        self.cpu = Cpu::new();
+        self.cpu.tracer = tracer;
+        self.ppu = Ppu::new();
+        self.mbc.reset(keep_memory);
+        self.frame_mcycles = 0;
+        self.is_lock_up_reported = false;
+        self.pacer.restart();
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(EmuEvent::Reset);
         }
-        println!("FINISHED TESTING CPU");
     }
 
-    //pub fn tick(&mut self, tile_frame: &mut [u8], game_frame: &mut [u8]) -> RenderState {
-    pub fn tick(&mut self, tw: &Arc<Mutex<Vec<u8>>>, bgmw: &Arc<Mutex<Vec<u8>>>, gw: &Arc<Mutex<Vec<u8>>>) -> PPUEvent {
-        if self.is_cpu_test_enabled && !self.is_cpu_tested {
-            self.test_mbc.is_testing_enabled = true;
-            self.test_cpu();
-            self.is_cpu_tested = true;
-            self.test_mbc.is_testing_enabled = false;
-        }
-
-        //joypad
-        {
-            let mut joypad_unlocked = self.joypad.lock().unwrap();
-            joypad_unlocked.sync_state(&mut self.mbc);
-        }
-
-        //
-        let mcycles_per_sec: u64 = 1_053_360;
-        let one_sec: u64 = 1;
-        let elapsed_time = self.current_time.elapsed().as_secs();
-        if elapsed_time < one_sec {
-            if self.sec_mcycles < mcycles_per_sec {
-                let mcycles = self.cpu.tick(&mut self.mbc);
-                self.sec_mcycles += mcycles;
-                self.ppu.tick(&mut self.mbc, tw, bgmw, gw, mcycles)
-            } else {
-                return PPUEvent::RenderEvent(RenderState::NoRender);
+    fn update_speed(&mut self) {
+        let speed = if self.is_fast_forward { self.fast_forward_speed } else { self.speed };
+        self.pacer.set_speed(speed);
+    }
+
+    pub fn start_recording(&mut self, file: &str) -> std::io::Result<()> {
+        self.stop_recording();
+        self.recorder = Some(VideoRecorder::new(file, 160, 144)?);
+        println!("recording video to {}", file);
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            match recorder.finish() {
+                Ok(frames) => println!("stopped recording after {} frames", frames),
+                Err(e) => println!("unable to finish recording: {}", e),
             }
-        }  else {
-            if elapsed_time > one_sec && self.debug == false {
-                panic!("ERROR: Elapsed time greater than one sick in EMU tic\n");
-            } else {
-                if self.sec_mcycles < mcycles_per_sec {
-                    print!("sec has elapsed without reaching max mcycles, current mcycle is {}\n", self.sec_mcycles);
-                }
-                else {
-                    print!("sec has elapsed and reached max mcycle\n");
-                }
-                self.sec_mcycles = 0;
-                self.current_time = Instant::now();
-                return PPUEvent::RenderEvent(RenderState::NoRender);
+        }
+    }
+
+    pub fn toggle_recording(&mut self) {
+        if self.recorder.is_some() {
+            self.stop_recording();
+        } else {
+            let file = format!("gbemu-{}.y4m", get_timestamp());
+            if let Err(e) = self.start_recording(&file) {
+                println!("unable to record to {}: {}", file, e);
             }
+        }
+    }
 
+    pub fn set_button(&mut self, button: Button, is_held: bool) {
+        if is_held {
+            self.input |= button.get_bit();
+        } else {
+            self.input &= !button.get_bit();
         }
+    }
 
+    // the same crc png chunks use, and the value no-intro lists for a rom
+    fn get_rom_crc32(&self) -> u32 {
+        self.mbc.rom.as_ref().map(|rom| crc32(&rom.data)).unwrap_or(0)
     }
 
-    pub fn tick_no_window(&mut self) -> PPUEvent {
-        let mcycle_per_sec: u64 = 1_053_360;
-        let one_sec: u64 = 1;
-        let elapsed_time = self.current_time.elapsed().as_secs();
-        if elapsed_time < one_sec {
-            if self.sec_mcycles < mcycle_per_sec {
-                let cycles = self.cpu.tick(&mut self.mbc);
-                self.sec_mcycles += cycles;
-                PPUEvent::RenderEvent(RenderState::NoRender)
-                //self.ppu.tick_no_window(&mut self.mbc, cycles)
-            } else {
-                PPUEvent::RenderEvent(RenderState::NoRender)
-            }
-        }  else {
-            if elapsed_time > one_sec {
-                panic!("ERROR: Elapsed time greater than one sick in EMU tick");
-            } else {
-                if self.sec_mcycles < mcycle_per_sec {
-                    print!("sec has elapsed without reaching max mcycles, current mcycle is {}\n", self.sec_mcycles);
+    // see savestate.rs, the rom's crc32 goes in so a state only loads on the cart it came from
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut state = STATE_MAGIC.to_vec();
+        state.push(STATE_VERSION);
+        self.get_rom_crc32().write_state(&mut state);
+        self.write_state(&mut state);
+        compress(&state)
+    }
+
+    // a state that turns out to be broken halfway through puts back the one the emu had
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), String> {
+        let state = decompress(data)?;
+        let mut input = StateReader::new(&state);
+        if input.take(4).ok() != Some(STATE_MAGIC.as_slice()) {
+            return Err(String::from("not a save state"));
+        }
+        let version = input.take(1)?[0];
+        if version != STATE_VERSION {
+            return Err(format!("save state version {}, this emu reads version {}", version, STATE_VERSION));
+        }
+        let mut rom_crc32 = 0u32;
+        rom_crc32.read_state(&mut input)?;
+        if rom_crc32 != self.get_rom_crc32() {
+            return Err(format!("save state is from a rom with crc32 {:08X}, the loaded rom's is {:08X}", rom_crc32, self.get_rom_crc32()));
+        }
+
+        let mut backup = Vec::new();
+        self.write_state(&mut backup);
+        let result = self.read_state(&mut input).and_then(|_| {
+            if input.is_done() { Ok(()) } else { Err(String::from("save state has bytes left over")) }
+        });
+        if result.is_err() {
+            self.read_state(&mut StateReader::new(&backup)).unwrap();
+        }
+        result
+    }
+
+    // at power-on the movie just says so, anything later starts it from a save state of right now
+    pub fn start_movie_recording(&mut self, file: &str, rom: &str) -> std::io::Result<()> {
+        let start = if self.cpu.total_mcycles == 0 { MovieStart::PowerOn } else { MovieStart::State(self.save_state()) };
+        let writer = MovieWriter::new(file, rom, self.get_rom_crc32(), start)?;
+        self.input_source = InputSource::Recording(writer);
+        println!("recording input movie to {}", file);
+        Ok(())
+    }
+
+    pub fn play_movie(&mut self, movie: Movie) -> std::io::Result<()> {
+        let rom_crc32 = self.get_rom_crc32();
+        if movie.rom_crc32 != rom_crc32 {
+            return Err(std::io::Error::other(format!(
+                "movie was recorded on {} (crc32 {:08X}), the loaded rom's crc32 is {:08X}", movie.rom, movie.rom_crc32, rom_crc32
+            )));
+        }
+        match &movie.start {
+            MovieStart::PowerOn if self.cpu.total_mcycles != 0 => {
+                return Err(std::io::Error::other("movie starts at power-on, the emu is already running"));
+            },
+            MovieStart::PowerOn => {},
+            MovieStart::State(data) => self.load_state(data).map_err(|e| std::io::Error::other(format!("movie's save state: {}", e)))?,
+        }
+        println!("playing input movie, {} frames", movie.frames.len());
+        self.input_source = InputSource::Playback(movie, 0);
+        Ok(())
+    }
+
+    pub fn stop_movie(&mut self) {
+        match std::mem::replace(&mut self.input_source, InputSource::Live) {
+            InputSource::Recording(writer) => match writer.finish() {
+                Ok(frames) => println!("stopped recording input movie after {} frames", frames),
+                Err(e) => println!("unable to finish input movie: {}", e),
+            },
+            InputSource::Playback(_, frame) => println!("stopped input movie at frame {}", frame),
+            InputSource::Live => {},
+        }
+    }
+
+    // input only changes between frames, so the same movie always lands on the same cycles
+    fn latch_input(&mut self) {
+        let buttons = match &mut self.input_source {
+            InputSource::Live => self.input,
+            InputSource::Recording(writer) => {
+                if let Err(e) = writer.write_frame(self.input) {
+                    println!("unable to write input movie, stopping recording: {}", e);
+                    self.input_source = InputSource::Live;
                 }
-                else {
-                    print!("sec has elapsed and reached max mcycle\n");
+                self.input
+            },
+            InputSource::Playback(movie, frame) => {
+                if let Some(&buttons) = movie.frames.get(*frame) {
+                    *frame += 1;
+                    buttons
+                } else {
+                    println!("input movie finished after {} frames, back to live input", movie.frames.len());
+                    self.input_source = InputSource::Live;
+                    self.input
                 }
-                self.sec_mcycles = 0;
-                self.current_time = Instant::now();
-                PPUEvent::RenderEvent(RenderState::NoRender)
+            },
+        };
+        if self.mbc.joypad.set_buttons(buttons) {
+            self.mbc.hw_reg.set_if_joypad_bit4();
+        }
+    }
+
+    // frames come from the ppu as they finish, not from the window, so a slow redraw never drops one
+    fn record_frame(&mut self, is_lcd_on: bool, gw: &Arc<Mutex<Vec<u8>>>) {
+        let Some(recorder) = self.recorder.as_mut() else { return; };
+        let result = if is_lcd_on {
+            recorder.write_frame(&gw.lock().unwrap())
+        } else {
+            // the lcd is off, keep the video in time with blank frames
+            recorder.write_blank_frame()
+        };
+        if let Err(e) = result {
+            println!("unable to write video frame, stopping recording: {}", e);
+            self.recorder = None;
+        }
+    }
+
+    // a frame ends when the ppu finishes one, or every 17556 mcycles while the lcd is off
+    fn end_frame(&mut self, ppu_event: PPUEvent, mcycles: u64, gw: &Arc<Mutex<Vec<u8>>>) {
+        self.frame_mcycles += mcycles;
+        let is_lcd_on = ppu_event == PPUEvent::RenderEvent(RenderState::Render);
+        if is_lcd_on {
+            self.frame_mcycles = 0;
+        } else if self.frame_mcycles >= MCYCLES_PER_FRAME {
+            self.frame_mcycles -= MCYCLES_PER_FRAME;
+        } else {
+            return;
+        }
+        self.record_frame(is_lcd_on, gw);
+        // the sgb picks up vram transfers and puts the border around the frame
+        if let Some(mut sgb) = self.mbc.sgb.take() {
+            sgb.end_frame(&self.mbc, &self.ppu.shade_buffer, &mut self.sgb_buffer.lock().unwrap());
+            self.mbc.sgb = Some(sgb);
+        }
+        self.latch_input();
+        if self.frames_to_advance > 0 {
+            self.frames_to_advance -= 1;
+            if self.frames_to_advance == 0 {
+                println!("advanced to frame end at mcycle {}", self.cpu.total_mcycles);
             }
+        }
+    }
 
+    fn report_lock_up(&mut self) {
+        if self.is_lock_up_reported {
+            return;
         }
-        // if self.sec_cycles < mcycle_per_sec && self.current_time.elapsed().as_secs() < one_sec {
-        //     let cycles = self.cpu.tick(&mut self.mbc, &self.bios);
-        //     self.sec_cycles += cycles;
-        //     self.ppu.tick(&mut self.mbc, tile_frame, game_frame, cycles)
-        // }
+        if let Some(lock_up) = self.cpu.locked_up {
+            self.is_lock_up_reported = true;
+            let location = self.symbols.as_ref()
+                .and_then(|symbols| symbols.describe(lock_up.bank, lock_up.pc))
+                .map(|label| format!(" in {}", label))
+                .unwrap_or_default();
+            println!("crash: illegal opcode 0x{:02X} at {:02X}:{:04X}{}", lock_up.opcode, lock_up.bank, lock_up.pc, location);
+            print_state(&self.cpu, &self.mbc, self.symbols.as_deref());
+            if self.debugger.is_console_attached() {
+                self.debugger.pause("CPU locked up", &self.cpu, &self.mbc);
+            }
+            if let Some(tx) = &self.event_tx {
+                // the frontend may have gone away, nothing to do about it here
+                let _ = tx.send(EmuEvent::LockUp(lock_up));
+            }
+        }
+    }
 
+    //pub fn tick(&mut self, tile_frame: &mut [u8], game_frame: &mut [u8]) -> RenderState {
+    pub fn tick(&mut self, tw: &Arc<Mutex<Vec<u8>>>, bgmw: &Arc<Mutex<Vec<u8>>>, gw: &Arc<Mutex<Vec<u8>>>) -> PPUEvent {
+        self.handle_commands();
+        if !self.is_running() {
+            // nothing to do until a command comes in, don't spin on it
+            // the debugger console still gets a look, step() isn't calling it
+            self.debugger.poll_commands(&self.cpu, &mut self.mbc);
+            std::thread::sleep(Duration::from_millis(10));
+            return PPUEvent::RenderEvent(RenderState::NoRender);
+        }
+        let ppu_event = self.step(tw, bgmw, gw);
+        self.pacer.add_mcycles(self.last_step_mcycles);
+        ppu_event
+    }
 
+    // runs one cpu step and everything it drives, without any pacing
+    pub fn step(&mut self, tw: &Arc<Mutex<Vec<u8>>>, bgmw: &Arc<Mutex<Vec<u8>>>, gw: &Arc<Mutex<Vec<u8>>>) -> PPUEvent {
+        self.last_step_mcycles = 0;
+        if let Some(gdb) = self.gdb.as_mut() {
+            if !gdb.before_step(&mut self.cpu, &mut self.mbc) {
+                return PPUEvent::RenderEvent(RenderState::NoRender);
+            }
+        }
+        if !self.debugger.before_step(&self.cpu, &mut self.mbc) {
+            return PPUEvent::RenderEvent(RenderState::NoRender);
+        }
+        // the ppu is stepped on every mcycle of the instruction instead of catching up after it
+        // frames and pacing go by its mcycles, in double speed the cpu gets two for each
+        let ppu = &mut self.ppu;
+        let mut ppu_event = PPUEvent::RenderEvent(RenderState::NoRender);
+        let mut ppu_mcycles = 0;
+        let mut tick_ppu = |mbc: &mut Mbc| {
+            ppu_mcycles += 1;
+            if ppu.tick(mbc, tw, bgmw, gw, 1) == PPUEvent::RenderEvent(RenderState::Render) {
+                ppu_event = PPUEvent::RenderEvent(RenderState::Render);
+            }
+        };
+        let mut bus = Bus::new(&mut self.mbc, &mut tick_ppu);
+        self.cpu.step(&mut bus);
+        self.report_lock_up();
+        if let Some(gdb) = self.gdb.as_mut() {
+            gdb.after_step(&self.cpu, &mut self.mbc);
+        }
+        self.debugger.after_step(&self.cpu, &mut self.mbc);
+        self.last_step_mcycles = ppu_mcycles;
+        self.end_frame(ppu_event, ppu_mcycles, gw);
+        ppu_event
+    }
 
+    pub fn tick_no_window(&mut self) -> PPUEvent {
+        let cycles = self.cpu.tick(&mut self.mbc);
+        self.pacer.add_mcycles(cycles);
+        self.report_lock_up();
+        PPUEvent::RenderEvent(RenderState::NoRender)
+        //self.ppu.tick_no_window(&mut self.mbc, cycles)
     }
 
-}
\ No newline at end of file
+}
+
+// game.gb saves to game.sav next to it
+pub fn get_save_file(rom_file: &str) -> String {
+    Path::new(rom_file).with_extension("sav").to_string_lossy().into_owned()
+}
+
+pub fn get_state_file(rom_file: &str) -> String {
+    Path::new(rom_file).with_extension("state").to_string_lossy().into_owned()
+}
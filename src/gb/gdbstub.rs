@@ -0,0 +1,460 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::gb::cpu::Cpu;
+use crate::gb::mbc::*;
+use crate::gb::debugger::Watchpoint;
+
+// gdb remote serial protocol server, one client at a time on localhost
+//
+// registers, in the order of g/G packets and p/P numbers, each 16 bits little endian:
+//   0 af, 1 bc, 2 de, 3 hl, 4 sp, 5 pc
+// so a g reply is 24 hex chars and af=0x01B0 goes over the wire as b001
+//
+// memory is the cpu's 64KB view: whatever rom and ram banks are mapped in right now,
+// io registers at ff00-ff7f, hram, and ie at ffff. writes go through the same path as cpu writes,
+// so writing to 0000-7fff switches banks instead of patching the rom
+//
+// Z0/Z1 are pc breakpoints, Z2/Z3/Z4 are write/read/access watchpoints on the mbc
+// the target and memory map descriptions below are served through qXfer for clients that ask
+
+pub const DEFAULT_GDB_PORT: u16 = 2345;
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.gbemu.sm83">
+    <reg name="af" bitsize="16" type="uint16" regnum="0"/>
+    <reg name="bc" bitsize="16" type="uint16"/>
+    <reg name="de" bitsize="16" type="uint16"/>
+    <reg name="hl" bitsize="16" type="uint16"/>
+    <reg name="sp" bitsize="16" type="data_ptr"/>
+    <reg name="pc" bitsize="16" type="code_ptr"/>
+  </feature>
+</target>"#;
+
+const MEMORY_MAP_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE memory-map PUBLIC "+//IDN gnu.org//DTD GDB Memory Map V1.0//EN" "http://sourceware.org/gdb/gdb-memory-map.dtd">
+<memory-map>
+  <memory type="rom" start="0x0000" length="0x8000"/>
+  <memory type="ram" start="0x8000" length="0x8000"/>
+</memory-map>"#;
+
+// how many instructions run between checks for a ctrl-c or a new client
+const POLL_INTERVAL: u32 = 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GdbState {
+    Waiting, // no client
+    Stopped,
+    Running,
+    Stepping,
+}
+
+enum Packet {
+    Command(String),
+    Interrupt,
+}
+
+pub struct GdbStub {
+    listener: TcpListener,
+    stream: Option<TcpStream>,
+    pub state: GdbState,
+    pub breakpoints: Vec<u16>,
+    watchpoints: Vec<(Watchpoint, &'static str)>, // the ones gdb put on the mbc and the stop reason each one reports
+    buf: Vec<u8>,
+    is_disconnected: bool,
+    steps_since_poll: u32,
+    skip_breakpoint_at: Option<u16>,
+    is_lock_up_reported: bool,
+}
+
+impl GdbStub {
+    pub fn new(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        println!("gdb stub listening on 127.0.0.1:{}", listener.local_addr()?.port());
+        Ok(GdbStub {
+            listener,
+            stream: None,
+            state: GdbState::Waiting,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            buf: Vec::new(),
+            is_disconnected: false,
+            steps_since_poll: 0,
+            skip_breakpoint_at: None,
+            is_lock_up_reported: false,
+        })
+    }
+
+    // port 0 in new picks a free one, this says which
+    pub fn get_port(&self) -> u16 {
+        self.listener.local_addr().map(|addr| addr.port()).unwrap_or(0)
+    }
+
+    // true if the cpu should run an instruction now
+    pub fn before_step(&mut self, cpu: &mut Cpu, mbc: &mut Mbc) -> bool {
+        if self.is_disconnected {
+            self.disconnect(mbc);
+        }
+        match self.state {
+            GdbState::Waiting => {
+                self.steps_since_poll += 1;
+                if self.steps_since_poll >= POLL_INTERVAL {
+                    self.steps_since_poll = 0;
+                    self.accept();
+                }
+                self.state != GdbState::Stopped
+            },
+            GdbState::Stopped => {
+                // block a little on the socket instead of spinning
+                if let Some(Packet::Command(command)) = self.read_packet(true) {
+                    self.handle_command(&command, cpu, mbc);
+                }
+                false
+            },
+            GdbState::Running | GdbState::Stepping => {
+                self.steps_since_poll += 1;
+                if self.steps_since_poll >= POLL_INTERVAL {
+                    self.steps_since_poll = 0;
+                    if let Some(Packet::Interrupt) = self.read_packet(false) {
+                        self.stop("S02");
+                        return false;
+                    }
+                }
+                let pc = cpu.registers.get_pc();
+                if self.skip_breakpoint_at != Some(pc) && self.breakpoints.contains(&pc) {
+                    self.stop("S05");
+                    return false;
+                }
+                self.skip_breakpoint_at = None;
+                true
+            },
+        }
+    }
+
+    pub fn after_step(&mut self, cpu: &Cpu, mbc: &mut Mbc) {
+        if self.state != GdbState::Running && self.state != GdbState::Stepping {
+            return;
+        }
+        if let Some(hit) = mbc.watch_hit.take() {
+            // the reason has to be the Z type gdb asked for, an access watchpoint reports awatch for reads and writes
+            // hits on the debugger's own watchpoints mean nothing to gdb and are a plain stop
+            match self.watchpoints.iter().find(|(wp, _)| wp.matches(hit.address, hit.kind)) {
+                Some((_, reason)) => {
+                    let reply = format!("T05{}:{:04x};", reason, hit.address);
+                    self.stop(&reply);
+                },
+                None => self.stop("S05"),
+            }
+            return;
+        }
+        if cpu.locked_up.is_some() && !self.is_lock_up_reported {
+            self.is_lock_up_reported = true;
+            self.stop("S04");
+            return;
+        }
+        if self.state == GdbState::Stepping {
+            self.stop("S05");
+        }
+    }
+
+    fn accept(&mut self) {
+        match self.listener.accept() {
+            Ok((stream, addr)) => {
+                println!("gdb client connected from {}", addr);
+                let _ = stream.set_nodelay(true);
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(10)));
+                self.stream = Some(stream);
+                self.buf.clear();
+                self.is_lock_up_reported = false;
+                // gdb expects the target to be halted when it attaches
+                self.state = GdbState::Stopped;
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {},
+            Err(e) => println!("gdb stub accept failed: {}", e),
+        }
+    }
+
+    fn disconnect(&mut self, mbc: &mut Mbc) {
+        println!("gdb client disconnected");
+        self.stream = None;
+        self.is_disconnected = false;
+        self.breakpoints.clear();
+        for (wp, _) in self.watchpoints.drain(..) {
+            if let Some(idx) = mbc.watchpoints.iter().position(|w| *w == wp) {
+                mbc.watchpoints.remove(idx);
+            }
+        }
+        self.state = GdbState::Waiting;
+    }
+
+    fn stop(&mut self, reply: &str) {
+        self.state = GdbState::Stopped;
+        self.send_packet(reply);
+    }
+
+    fn resume(&mut self, state: GdbState, cpu: &Cpu) {
+        self.skip_breakpoint_at = Some(cpu.registers.get_pc());
+        self.state = state;
+    }
+
+    // wait blocks for the read timeout, otherwise only what already arrived is picked up
+    // a packet with a bad checksum gets a - so the client sends it again
+    fn read_packet(&mut self, wait: bool) -> Option<Packet> {
+        let stream = self.stream.as_mut()?;
+        let _ = stream.set_nonblocking(!wait);
+        let mut chunk = [0u8; 1024];
+        match stream.read(&mut chunk) {
+            Ok(0) => {
+                self.is_disconnected = true;
+                return None;
+            },
+            Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {},
+            Err(_) => {
+                self.is_disconnected = true;
+                return None;
+            },
+        }
+
+        // acks from the client are dropped, the stub never resends
+        while let Some(&b) = self.buf.first() {
+            if b == b'+' || b == b'-' {
+                self.buf.remove(0);
+            } else {
+                break;
+            }
+        }
+        if self.buf.first() == Some(&0x03) {
+            self.buf.remove(0);
+            return Some(Packet::Interrupt);
+        }
+        // $data#xx, wait until the checksum is in
+        let start = self.buf.iter().position(|&b| b == b'$')?;
+        let end = start + self.buf[start..].iter().position(|&b| b == b'#')?;
+        if self.buf.len() < end + 3 {
+            return None;
+        }
+        let data = String::from_utf8_lossy(&self.buf[start + 1..end]).to_string();
+        let checksum = std::str::from_utf8(&self.buf[end + 1..end + 3]).ok().and_then(|cs| u8::from_str_radix(cs, 16).ok());
+        let is_valid = checksum == Some(self.buf[start + 1..end].iter().fold(0u8, |sum, &b| sum.wrapping_add(b)));
+        self.buf.drain(..end + 3);
+        if let Some(stream) = self.stream.as_mut() {
+            let _ = stream.write_all(if is_valid { b"+" } else { b"-" });
+        }
+        if !is_valid {
+            return None;
+        }
+        Some(Packet::Command(data))
+    }
+
+    fn send_packet(&mut self, data: &str) {
+        let Some(stream) = self.stream.as_mut() else { return; };
+        let checksum = data.bytes().fold(0u8, |sum, b| sum.wrapping_add(b));
+        let packet = format!("${}#{:02x}", data, checksum);
+        if stream.write_all(packet.as_bytes()).is_err() {
+            self.is_disconnected = true;
+        }
+    }
+
+    fn handle_command(&mut self, command: &str, cpu: &mut Cpu, mbc: &mut Mbc) {
+        let reply = match command.as_bytes().first() {
+            Some(b'?') => String::from("S05"),
+            Some(b'g') => {
+                (0..6).map(|reg| encode_u16(read_register(cpu, reg))).collect::<String>()
+            },
+            Some(b'G') => {
+                let data = &command[1..];
+                for reg in 0..6 {
+                    if let Some(val) = data.get(reg * 4..reg * 4 + 4).and_then(decode_u16) {
+                        write_register(cpu, reg, val);
+                    }
+                }
+                String::from("OK")
+            },
+            Some(b'p') => match usize::from_str_radix(&command[1..], 16) {
+                Ok(reg) if reg < 6 => encode_u16(read_register(cpu, reg)),
+                _ => String::from("E01"),
+            },
+            Some(b'P') => {
+                let parsed = command[1..].split_once('=').and_then(|(reg, val)| {
+                    Some((usize::from_str_radix(reg, 16).ok()?, decode_u16(val)?))
+                });
+                match parsed {
+                    Some((reg, val)) if reg < 6 => {
+                        write_register(cpu, reg, val);
+                        String::from("OK")
+                    },
+                    _ => String::from("E01"),
+                }
+            },
+            Some(b'm') => match parse_addr_len(&command[1..]) {
+                Some((addr, len)) => {
+                    (0..len).map(|i| format!("{:02x}", mbc.read(addr.wrapping_add(i), OpSource::Debugger))).collect()
+                },
+                None => String::from("E01"),
+            },
+            Some(b'M') => {
+                let parsed = command[1..].split_once(':').and_then(|(range, data)| Some((parse_addr_len(range)?, data)));
+                match parsed {
+                    Some(((addr, len), data)) => {
+                        for i in 0..len {
+                            let idx = i as usize * 2;
+                            if let Some(byte) = data.get(idx..idx + 2).and_then(|b| u8::from_str_radix(b, 16).ok()) {
+                                mbc.write(addr.wrapping_add(i), byte, OpSource::Debugger);
+                            }
+                        }
+                        String::from("OK")
+                    },
+                    None => String::from("E01"),
+                }
+            },
+            Some(b'c') => {
+                self.resume(GdbState::Running, cpu);
+                return;
+            },
+            Some(b's') => {
+                self.resume(GdbState::Stepping, cpu);
+                return;
+            },
+            Some(b'Z') | Some(b'z') => self.handle_breakpoint(command, mbc),
+            Some(b'k') => {
+                self.disconnect(mbc);
+                return;
+            },
+            Some(b'D') => {
+                self.send_packet("OK");
+                self.disconnect(mbc);
+                return;
+            },
+            Some(b'H') => String::from("OK"),
+            Some(b'q') => self.handle_query(command),
+            // vCont and anything else we don't know gets the empty reply so gdb falls back
+            _ => String::new(),
+        };
+        self.send_packet(&reply);
+    }
+
+    // Z0,addr,kind inserts, z0,addr,kind removes
+    fn handle_breakpoint(&mut self, command: &str, mbc: &mut Mbc) -> String {
+        let insert = command.starts_with('Z');
+        let fields: Vec<&str> = command[1..].split(',').collect();
+        let (Some(kind), Some(addr)) = (fields.first(), fields.get(1).and_then(|a| u16::from_str_radix(a, 16).ok())) else {
+            return String::from("E01");
+        };
+        let len = fields.get(2).and_then(|l| u16::from_str_radix(l, 16).ok()).unwrap_or(1).max(1);
+        match *kind {
+            "0" | "1" => {
+                if insert {
+                    if !self.breakpoints.contains(&addr) {
+                        self.breakpoints.push(addr);
+                    }
+                } else {
+                    self.breakpoints.retain(|&bp| bp != addr);
+                }
+                String::from("OK")
+            },
+            "2" | "3" | "4" => {
+                let wp = Watchpoint {
+                    start: addr,
+                    end: addr.wrapping_add(len - 1),
+                    read: *kind != "2",
+                    write: *kind != "3",
+                    execute: false,
+                };
+                let reason = match *kind {
+                    "2" => "watch",
+                    "3" => "rwatch",
+                    _ => "awatch",
+                };
+                if insert {
+                    self.watchpoints.push((wp, reason));
+                    mbc.watchpoints.push(wp);
+                } else {
+                    self.watchpoints.retain(|(w, _)| *w != wp);
+                    mbc.watchpoints.retain(|w| *w != wp);
+                }
+                String::from("OK")
+            },
+            _ => String::new(),
+        }
+    }
+
+    fn handle_query(&mut self, command: &str) -> String {
+        if command.starts_with("qSupported") {
+            String::from("PacketSize=4000;qXfer:features:read+;qXfer:memory-map:read+")
+        } else if command == "qAttached" {
+            String::from("1")
+        } else if command == "qC" {
+            String::from("QC1")
+        } else if command == "qfThreadInfo" {
+            String::from("m1")
+        } else if command == "qsThreadInfo" {
+            String::from("l")
+        } else if let Some(args) = command.strip_prefix("qXfer:features:read:target.xml:") {
+            xfer_chunk(TARGET_XML, args)
+        } else if let Some(args) = command.strip_prefix("qXfer:memory-map:read::") {
+            xfer_chunk(MEMORY_MAP_XML, args)
+        } else {
+            String::new()
+        }
+    }
+}
+
+fn read_register(cpu: &Cpu, reg: usize) -> u16 {
+    let regs = &cpu.registers;
+    match reg {
+        0 => regs.get_af(),
+        1 => regs.get_bc(),
+        2 => regs.get_de(),
+        3 => regs.get_hl(),
+        4 => regs.get_sp(),
+        _ => regs.get_pc(),
+    }
+}
+
+fn write_register(cpu: &mut Cpu, reg: usize, val: u16) {
+    let regs = &mut cpu.registers;
+    match reg {
+        // the low nibble of f doesn't exist
+        0 => regs.set_af(val & 0xFFF0),
+        1 => regs.set_bc(val),
+        2 => regs.set_de(val),
+        3 => regs.set_hl(val),
+        4 => regs.set_sp(val),
+        _ => regs.set_pc(val),
+    }
+}
+
+// 16 bit values go over the wire little endian
+fn encode_u16(val: u16) -> String {
+    format!("{:02x}{:02x}", val & 0xFF, val >> 8)
+}
+
+fn decode_u16(text: &str) -> Option<u16> {
+    let lo = u8::from_str_radix(text.get(0..2)?, 16).ok()?;
+    let hi = u8::from_str_radix(text.get(2..4)?, 16).ok()?;
+    Some(u16::from_le_bytes([lo, hi]))
+}
+
+fn parse_addr_len(text: &str) -> Option<(u16, u16)> {
+    let (addr, len) = text.split_once(',')?;
+    Some((u16::from_str_radix(addr, 16).ok()?, u16::from_str_radix(len, 16).ok()?))
+}
+
+// offset,length out of a qXfer document, m means there's more and l means last
+fn xfer_chunk(doc: &str, args: &str) -> String {
+    let Some((offset, len)) = args.split_once(',') else { return String::from("E01"); };
+    let (Ok(offset), Ok(len)) = (usize::from_str_radix(offset, 16), usize::from_str_radix(len, 16)) else {
+        return String::from("E01");
+    };
+    if offset >= doc.len() {
+        return String::from("l");
+    }
+    let end = (offset + len).min(doc.len());
+    let prefix = if end == doc.len() { "l" } else { "m" };
+    format!("{}{}", prefix, &doc[offset..end])
+}
@@ -24,6 +24,8 @@ pub enum Layer {
     SPRITE,
 }
 
+crate::impl_state_enum!(Layer { BG, WIN, SPRITE });
+
 
 pub struct Fetcher {
     pub window_layer_active_in_lcdc: bool,
@@ -43,6 +45,11 @@ pub struct Fetcher {
     pub current_tile_low_byte: u8,
     pub current_tile_high_byte: u8,
     pub current_priority: bool,
+    // CGB attributes of the tile being fetched, the bg's come from vram bank 1 at the same map address
+    // and a sprite's are its OAM flags, both are laid out the same
+    // bit 0-2 palette, 3 vram bank, 4 dmg palette (sprites only), 5 x flip, 6 y flip, 7 priority
+    pub current_attributes: u8,
+    pub current_sprite_y: u8, // OAM Y of the sprite being fetched, its row on this line counts from there
     pub need_to_resume: bool,
     pub pixels_to_mark_skipped: u8,
     pub fetcher_end_of_scanline: bool,
@@ -50,6 +57,13 @@ pub struct Fetcher {
 
 }
 
+crate::impl_state!(Fetcher {
+    window_layer_active_in_lcdc, active_layer, start_of_rendering, tile_x_pos, tile_y_pos, win_x_pos,
+    win_y_pos, tcycle_budget, row_in_tile, current_step, current_tile_num, current_tile_low_byte,
+    current_tile_high_byte, current_priority, current_attributes, current_sprite_y, need_to_resume,
+    pixels_to_mark_skipped, fetcher_end_of_scanline, finished_sprites_in_scanline,
+});
+
 impl Fetcher {
     pub fn new() -> Self {
         Fetcher {
@@ -70,6 +84,8 @@ impl Fetcher {
             current_tile_low_byte: 0,
             current_tile_high_byte: 0,
             current_priority: false,
+            current_attributes: 0,
+            current_sprite_y: 0,
             need_to_resume: false,
             pixels_to_mark_skipped: 0,
             fetcher_end_of_scanline: false,
@@ -77,6 +93,34 @@ impl Fetcher {
         }
     }
 
+    fn get_attributes_vram_bank(&self) -> u8 {
+        (self.current_attributes >> 3) & 1
+    }
+
+    fn is_attributes_x_flipped(&self) -> bool {
+        self.current_attributes & 0b0010_0000 != 0
+    }
+
+    // the row inside the tile, upside down when it's flipped
+    fn get_attributes_row_offset(&self, fine_y: u16) -> u16 {
+        if self.current_attributes & 0b0100_0000 != 0 {
+            2 * (7 - fine_y)
+        } else {
+            2 * fine_y
+        }
+    }
+
+    // the sprite's row on this line, OAM Y is 16 above the top of the screen
+    // 8x16 sprites are the even tile on top of the odd one and flip as a whole
+    pub fn get_sprite_row_address(&self, mbc: &Mbc, tile_num: usize) -> u16 {
+        let is_tall = mbc.hw_reg.is_lcdc_obj_size_bit2_enabled();
+        let height: u16 = if is_tall { 16 } else { 8 };
+        let tile_num = if is_tall { tile_num as u16 & 0xFE } else { tile_num as u16 };
+        let row = (mbc.hw_reg.ly as u16 + 16).wrapping_sub(self.current_sprite_y as u16) % height;
+        let row = if self.current_attributes & 0b0100_0000 != 0 { height - 1 - row } else { row };
+        0x8000 + tile_num * 16 + row * 2
+    }
+
     pub fn get_tile_map_address_in_bg_win_step_1(&self, mbc: &Mbc) -> u16 {
         if mbc.hw_reg.is_lcdc_window_enable_bit5_enabled() {
             // are we in a window pixel
@@ -159,7 +203,9 @@ impl Fetcher {
             //let tile_index = mbc.read(tile_base_add  + (32 * (self.win_y_pos / 8)) as u16, OpSource::PPU) as usize;
             let win_x = (self.win_x_pos / 8) & 0x1F;
             let win_y = (self.win_y_pos / 8) * 32;
-            let tile_index = mbc.read(tile_base_add + win_x as u16 + win_y as u16, OpSource::PPU) as usize;
+            let map_address = tile_base_add + win_x as u16 + win_y as u16;
+            let tile_index = mbc.read(map_address, OpSource::PPU) as usize;
+            self.current_attributes = if mbc.is_cgb_mode() { mbc.read_vram(1, map_address) } else { 0 };
             // not needed as I have win x and y
             // self.row_in_tile += 1;
             // //reset to row 0 when we go to a new tile
@@ -177,6 +223,7 @@ impl Fetcher {
             // handles pixel row
             let y = (((mbc.hw_reg.ly as u16 + mbc.hw_reg.scy as u16) & 0xFF) / 8) * 32;
             let tile_index = mbc.read(tile_base_add +  x + y, OpSource::PPU) as usize;
+            self.current_attributes = if mbc.is_cgb_mode() { mbc.read_vram(1, tile_base_add + x + y) } else { 0 };
             Ok(tile_index)
         }
     }
@@ -192,10 +239,10 @@ impl Fetcher {
         // Determine which tile data area
         let use_unsigned = mbc.hw_reg.is_lcdc_bg_win_tile_data_area_bit4_enabled();
         let row_offset: u16 = if self.active_layer == Layer::WIN {
-            2 * (self.win_y_pos as u16 % 8)
+            self.get_attributes_row_offset(self.win_y_pos as u16 % 8)
         } else {
             let fine_y = ((mbc.hw_reg.ly as u16 + mbc.hw_reg.scy as u16) & 0xFF) % 8;
-            2 * fine_y
+            self.get_attributes_row_offset(fine_y)
         };
 
         let addr = if use_unsigned {
@@ -207,7 +254,7 @@ impl Fetcher {
             (0x9000i32 + (tile_num_signed as i32) * 16 + row_offset as i32) as u16
         };
 
-        Ok(mbc.read(addr, OpSource::PPU))
+        Ok(mbc.read_vram(self.get_attributes_vram_bank(), addr))
     }
 
 
@@ -225,10 +272,10 @@ impl Fetcher {
 
         let use_unsigned = mbc.hw_reg.is_lcdc_bg_win_tile_data_area_bit4_enabled();
         let row_offset: u16 = if self.active_layer == Layer::WIN {
-            2 * (self.win_y_pos as u16 % 8)
+            self.get_attributes_row_offset(self.win_y_pos as u16 % 8)
         } else {
             let fine_y = ((mbc.hw_reg.ly as u16 + mbc.hw_reg.scy as u16) & 0xFF) % 8;
-            2 * fine_y
+            self.get_attributes_row_offset(fine_y)
         };
 
         let addr = if use_unsigned {
@@ -238,7 +285,7 @@ impl Fetcher {
             (0x9000i32 + (signed_index as i32) * 16 + row_offset as i32 + 1) as u16
         };
 
-        Ok(mbc.read(addr, OpSource::PPU))
+        Ok(mbc.read_vram(self.get_attributes_vram_bank(), addr))
     }
 
 
@@ -257,8 +304,11 @@ impl Fetcher {
             }
         }
 
-        // todo if tile is flipped horizontally push lsb first, else push msb first
-        let raw_pixels = GBPixel::decode_pixels_from_bytes(tile_low_byte, tile_high_byte);
+        // a flipped tile pushes its lsb first
+        let mut raw_pixels = GBPixel::decode_pixels_from_bytes(tile_low_byte, tile_high_byte);
+        if self.is_attributes_x_flipped() {
+            raw_pixels.reverse();
+        }
         for p in raw_pixels {
 
             let skip = if self.pixels_to_mark_skipped > 0 {
@@ -268,10 +318,12 @@ impl Fetcher {
                 false
             };
 
+            // on a cgb bit 7 puts the bg over sprites, attributes are 0 on a dmg
             fifo.push(GBPixel {
                 color: p,
-                bg_priority: false,
+                bg_priority: self.current_attributes & 0b1000_0000 != 0,
                 skip,
+                palette: self.current_attributes & 0b111,
             });
         }
         if self.active_layer == Layer::WIN {
@@ -296,10 +348,7 @@ impl Fetcher {
             //     }
             // }
         }
-        // needed because it's used in the scan line calc in func get_tile_map_address_in_bg_win_step_1
-        if self.tile_x_pos == 20 {
-            self.tile_x_pos = 0;
-        }
+        // tile_x_pos starts over with every line, a line scrolled by scx % 8 needs a 21st tile
 
         Ok(())
     }
@@ -552,6 +601,7 @@ impl Fetcher {
                 Ok((tile_num, priority)) => {
                     //print!("inside handle_sprite_layer\n");
                     self.current_tile_num = tile_num as u16;
+                    self.current_priority = priority;
                     let low_byte = match self.sprite_step_2_fetch_tile_data_low(mbc, self.current_tile_num as usize) {
                         Ok(low_byte) => {
                             self.current_tile_low_byte = low_byte;
@@ -634,6 +684,9 @@ impl Fetcher {
                  //self.dot_in_scanline += 8;
                 sprite_num = x.byte2_tile_num as usize;
                 sprite_priority = x.get_byte3_sprite_flags_bit7_priority();
+                // the cgb palette and bank bits don't exist on a dmg
+                self.current_attributes = if mbc.is_cgb_mode() { x.byte3_sprite_flags } else { x.byte3_sprite_flags & 0xF0 };
+                self.current_sprite_y = x.byte0_y_pos;
                 idx_to_remove = i;
                 found_sprite = true;
                 break;
@@ -700,12 +753,7 @@ impl Fetcher {
             return Err(FetcherError::NotEnoughTcycles)
         }
         self.tcycle_budget -= 2;
-        // handle 0x8000
-        let address: u16 =  0x8000;
-
-        let fine_y = ((mbc.hw_reg.ly as u16 + mbc.hw_reg.scy as u16) & 0xFF) % 8;
-        let row_offset = 2 * fine_y;
-        return Ok(mbc.read(address + (tile_num as u16 * 16) + row_offset, OpSource::PPU))
+        Ok(mbc.read_vram(self.get_attributes_vram_bank(), self.get_sprite_row_address(mbc, tile_num)))
     }
 
     // pub fn sprite_step_3_fetch_tile_data_high(&mut self, mbc: &Mbc, tile_num: usize) -> Result<u8, FetcherError>  {
@@ -731,12 +779,7 @@ impl Fetcher {
             return Err(FetcherError::NotEnoughTcycles)
         }
         self.tcycle_budget -= 2;
-        // handle 0x8000
-        let address: u16 =  0x8000;
-
-        let fine_y = ((mbc.hw_reg.ly as u16 + mbc.hw_reg.scy as u16) & 0xFF) % 8;
-        let row_offset = 2 * fine_y;
-        return Ok(mbc.read(address + (tile_num as u16 * 16) + row_offset + 1, OpSource::PPU))
+        Ok(mbc.read_vram(self.get_attributes_vram_bank(), self.get_sprite_row_address(mbc, tile_num) + 1))
     }
 
 
@@ -756,8 +799,12 @@ impl Fetcher {
             fifo.data.clear();
         }
 
-        // todo if tile is flipped horizontally push lsb first, else push msb first
-        let raw_pixels = GBPixel::decode_pixels_from_bytes(tile_low_byte, tile_high_byte);
+        let mut raw_pixels = GBPixel::decode_pixels_from_bytes(tile_low_byte, tile_high_byte);
+        if self.is_attributes_x_flipped() {
+            raw_pixels.reverse();
+        }
+        // cgb sprites pick one of 8 palettes, dmg ones OBP0 or OBP1
+        let palette = if mbc.is_cgb_mode() { self.current_attributes & 0b111 } else { (self.current_attributes >> 4) & 1 };
         for p in raw_pixels {
 
             let skip = if self.pixels_to_mark_skipped > 0 {
@@ -773,6 +820,7 @@ impl Fetcher {
                 color: p,
                 bg_priority: priority,
                 skip,
+                palette,
             });
         }
 
@@ -10,6 +10,9 @@ pub struct Fifo {
     pub max_size: usize,
 }
 
+// max_size never changes
+crate::impl_state!(Fifo { data });
+
 impl Fifo {
     pub fn new() -> Self {
         Fifo {
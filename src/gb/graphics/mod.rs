@@ -5,4 +5,4 @@ pub mod ppu;
 pub mod sprite;
 pub mod fetcher;
 pub mod fifo;
-mod pixel;
\ No newline at end of file
+pub mod pixel;
\ No newline at end of file
@@ -3,8 +3,9 @@
 
 // palette is set via hardware register (mem location) 0xFF47, BG palette data aka BGP
 
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Debug, Default, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
 pub enum PaletteColor {
+    #[default]
     White,
     LightGray,
     DarkGray,
@@ -12,6 +13,8 @@ pub enum PaletteColor {
     Transparent,
 }
 
+crate::impl_state_enum!(PaletteColor { White, LightGray, DarkGray, Black, Transparent });
+
 impl PaletteColor {
     pub fn from_u8(n: u8) -> Self {
         match n {
@@ -34,6 +37,19 @@ impl PaletteColor {
     }
 }
 
+// a color out of CGB palette ram, 2 bytes of RGB555 little endian per color and 8 bytes per palette
+pub fn get_cgb_rgba_code(palette_ram: &[u8; 64], palette: u8, color_id: u8) -> [u8; 4] {
+    let idx = ((palette & 0b111) as usize) * 8 + (color_id & 0b11) as usize * 2;
+    get_rgb555_rgba_code(u16::from_le_bytes([palette_ram[idx], palette_ram[idx + 1]]))
+}
+
+// cgb and sgb colors are both RGB555, red in the low bits
+pub fn get_rgb555_rgba_code(color: u16) -> [u8; 4] {
+    // 5 bits up to 8, the low bits repeat the high ones so 31 is 255
+    let expand = |c: u16| -> u8 { ((c << 3) | (c >> 2)) as u8 };
+    [expand(color & 0x1F), expand((color >> 5) & 0x1F), expand((color >> 10) & 0x1F), 255]
+}
+
 pub struct BGPalette {
     id0: PaletteColor,
     id1: PaletteColor,
@@ -1,7 +1,7 @@
 use crate::gb::graphics::palette::*;
 
 
-#[derive(Debug, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Debug, Default, Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
 // only implementing enough for DMG not CGB.  Palette and sprite priority would be different for CGB.
 pub struct GBPixel {
     pub color: PaletteColor,
@@ -11,14 +11,18 @@ pub struct GBPixel {
     // background priority is not skipped
     pub bg_priority: bool,
     pub skip: bool,
+    pub palette: u8, // CGB palette 0-7, or OBP0/OBP1 for a dmg sprite
 }
 
+crate::impl_state!(GBPixel { color, bg_priority, skip, palette });
+
 impl GBPixel {
     pub fn new() -> Self {
         GBPixel {
             color: PaletteColor::White,
             bg_priority: false,
             skip: false,
+            palette: 0,
         }
     }
     pub fn decode_pixels_from_bytes(byte1: u8, byte2: u8) -> [PaletteColor; 8] {
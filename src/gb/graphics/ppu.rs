@@ -43,6 +43,8 @@ pub enum PPUMode {
     Mode_3_Draw,
 }
 
+crate::impl_state_enum!(PPUMode { Mode_0_H_Blank, Mode_1_V_Blank, Mode_2_OAM_Scan, Mode_3_Draw });
+
 
 
 pub struct Ppu {
@@ -66,6 +68,7 @@ pub struct Ppu {
     // pub active: bool,
     pub tcycle_in_mode_3_draw: u64,
     pub pixel_in_frame: u64,
+    pub shade_buffer: Vec<u8>, // 160x144, the dmg shade 0-3 of every pixel drawn after BGP/OBP0/OBP1, the sgb colors the frame with these
     drew_tiles_in_mode_3: bool,
     pub  mode_1_v_blank_first_scan_line: u8,
     pub  mode_0_h_blank_first_tcycle: u64,
@@ -74,6 +77,15 @@ pub struct Ppu {
     pub  mode_2_oam_scan_current_tcycle: u16,
     pub mode: PPUMode,
 }
+
+// tiles and bg_tile_map are only for the debug windows, they're redrawn from vram
+crate::impl_state!(Ppu {
+    fetcher, bg_win_fifo, sprite_fifo, tcycle_in_frame, tcycle_in_scanline, pixel_in_scanline,
+    started_mode_0_in_scanline, started_mode_1_in_frame, started_mode_2_in_scanline,
+    started_mode_3_in_scanline, sprites, ppu_init_complete, tcycle_in_mode_3_draw, pixel_in_frame,
+    shade_buffer, drew_tiles_in_mode_3, mode_1_v_blank_first_scan_line, mode_0_h_blank_first_tcycle,
+    mode_3_drawing_first_tcycle, mode_2_oam_scan_last_tcycle, mode_2_oam_scan_current_tcycle, mode,
+});
 impl Ppu {
     pub fn new() -> Self {
         Ppu {
@@ -96,6 +108,7 @@ impl Ppu {
             // active: false,
             tcycle_in_mode_3_draw: 0,
             pixel_in_frame: 0,
+            shade_buffer: vec![0; 160 * 144],
             drew_tiles_in_mode_3: false,
             mode_1_v_blank_first_scan_line: 144,
             mode_0_h_blank_first_tcycle: 369,
@@ -485,7 +498,46 @@ impl Ppu {
     //     *buffer = temp_buffer;
     // }
 
-    pub fn push_pixel_and_advance_counter(&mut self, gw_buffer_unlocked: &mut MutexGuard<Vec<u8>>, px: GBPixel ) -> Result<(), PPUEvent> {
+    // dmg greys come from the shade, a cgb looks its colors up in palette ram
+    pub fn get_pixel_rgba_code(mbc: &Mbc, px: &GBPixel, is_sprite: bool) -> [u8; 4] {
+        if !mbc.is_cgb {
+            return PaletteColor::from_u8(Self::get_dmg_shade(mbc, px, is_sprite)).get_rgba_code();
+        }
+        let palette_ram = if is_sprite { &mbc.hw_reg.obj_palette_ram } else { &mbc.hw_reg.bg_palette_ram };
+        if mbc.is_cgb_mode() {
+            get_cgb_rgba_code(palette_ram, px.palette, px.color as u8 & 0b11)
+        } else {
+            // compatibility mode, the shade picks the color out of the boot rom's palettes 0 and 1
+            get_cgb_rgba_code(palette_ram, px.palette, Self::get_dmg_shade(mbc, px, is_sprite))
+        }
+    }
+
+    // BGP, or OBP0/OBP1 for a sprite, maps the color id to the shade 0-3 the lcd shows
+    pub fn get_dmg_shade(mbc: &Mbc, px: &GBPixel, is_sprite: bool) -> u8 {
+        let dmg_palette = if !is_sprite { mbc.hw_reg.bgp } else if px.palette == 1 { mbc.hw_reg.obp1 } else { mbc.hw_reg.obp0 };
+        (dmg_palette >> ((px.color as u8 & 0b11) * 2)) & 0b11
+    }
+
+    // color 0 of a sprite is see through, otherwise the priority bits decide
+    pub fn is_sprite_pixel_drawn(mbc: &Mbc, bg_px: &GBPixel, sp_px: &GBPixel) -> bool {
+        if sp_px.color == PaletteColor::White {
+            return false;
+        }
+        if bg_px.color == PaletteColor::White {
+            return true;
+        }
+        if mbc.is_cgb_mode() {
+            // with LCDC bit 0 off sprites go over everything
+            if !mbc.hw_reg.is_lcdc_bg_win_enable_priority_bit0_enabled() {
+                return true;
+            }
+            !bg_px.bg_priority && !sp_px.bg_priority
+        } else {
+            !sp_px.bg_priority
+        }
+    }
+
+    pub fn push_pixel_and_advance_counter(&mut self, mbc: &Mbc, gw_buffer_unlocked: &mut MutexGuard<Vec<u8>>, px: GBPixel, is_sprite: bool) -> Result<(), PPUEvent> {
         // pushing px and fetching occur simultaneously
         //if self.fetcher.tcycle_budget == 0 { return Ok(()); }
 
@@ -494,15 +546,18 @@ impl Ppu {
         // todo validate logic of skipping pixels for sprites
         // return white if the pixel is to be skipped, this is for the horizontal per-pixel scrolling
         let rgba =  if !px.skip {
-            px.color.get_rgba_code()
+            Self::get_pixel_rgba_code(mbc, &px, is_sprite)
         } else {
             //print!("pixel is to be skipped\n");
             [0xFF, 0xFF, 0xFF, 0xFF]
         };
+        // every line starts at its own row, however many pixels the last one got to
+        self.pixel_in_frame = mbc.hw_reg.ly as u64 * 160 + self.pixel_in_scanline;
         gw_buffer_unlocked[(self.pixel_in_frame as usize) * 4..(self.pixel_in_frame as usize) * 4 + 4 ] .copy_from_slice(&rgba);
+        self.shade_buffer[self.pixel_in_frame as usize] = if px.skip { 0 } else { Self::get_dmg_shade(mbc, &px, is_sprite) };
         self.pixel_in_frame += 1;
         self.pixel_in_scanline += 1;
-        if self.pixel_in_scanline == 159 {
+        if self.pixel_in_scanline == 160 {
             self.pixel_in_scanline = 0;
             return Err(PPUEvent::EndOfScanLine)
         }
@@ -513,7 +568,7 @@ impl Ppu {
 
         if !self.ppu_init_complete { return Err(PPUEvent::InitNotComplete); }
         let buffer_len: u64 = 92160;
-        if (self.pixel_in_frame * 4) + 4 >= buffer_len {
+        if mbc.hw_reg.ly >= 144 || (self.pixel_in_frame * 4) + 4 > buffer_len {
             //print!("PPU pixel buffer is too small in mode_3_mix_pixels_and_draw");
             return Err(PPUEvent::BufferOverflow)
         }
@@ -528,22 +583,24 @@ impl Ppu {
 
             }
                 match (self.bg_win_fifo.pop(), self.sprite_fifo.pop()) {
+                // the scx % 8 pixels fetched at the start of a line are thrown away, not drawn
+                (Ok(bg_px), Err(_)) if bg_px.skip => {},
                 (Ok(bg_px), Err(_)) => {
                     // push bg_px
-                   self.push_pixel_and_advance_counter(&mut gw_buffer_unlocked, bg_px)?
+                   self.push_pixel_and_advance_counter(mbc, &mut gw_buffer_unlocked, bg_px, false)?
                 },
                 (Err(_), Ok(sp_px)) => {
                     // push sp_px
-                    self.push_pixel_and_advance_counter(&mut gw_buffer_unlocked, sp_px)?
+                    self.push_pixel_and_advance_counter(mbc, &mut gw_buffer_unlocked, sp_px, true)?
                 },
                 (Ok(bg_px), Ok(sp_px)) => {
-                    if bg_px.bg_priority && bg_px.color != PaletteColor::White {
+                    if !Self::is_sprite_pixel_drawn(mbc, &bg_px, &sp_px) {
                         // push_bg_px
-                        self.push_pixel_and_advance_counter(&mut gw_buffer_unlocked, bg_px)?
+                        self.push_pixel_and_advance_counter(mbc, &mut gw_buffer_unlocked, bg_px, false)?
 
                     } else {
                         // push sp_px
-                        self.push_pixel_and_advance_counter(&mut gw_buffer_unlocked, sp_px)?
+                        self.push_pixel_and_advance_counter(mbc, &mut gw_buffer_unlocked, sp_px, true)?
 
                     }
                 },
@@ -839,6 +896,8 @@ impl Ppu {
             mbc.hw_reg.ly = 0;
             self.tcycle_in_scanline = 0;
             self.tcycle_in_frame = 0;
+            // vram is free with the lcd off, even if it went off mid mode 3
+            mbc.restrict_vram_access = false;
             //mbc.hw_reg.stat = 0;
             // Clear mode bits (0–1) and coincidence flag (bit 2) but preserve interrupt enable bits (3,4,5,6):
             mbc.hw_reg.stat = mbc.hw_reg.stat & 0b1111_1000;
@@ -932,6 +991,9 @@ impl Ppu {
                 //self.sprites_in_oam_idx = 0;
                 // always reset pixels in frame because that's what the buffer writer uses as an index
                 self.pixel_in_scanline = 0;
+                // mode 3 ends when the line's 160 pixels are out, that's different every line
+                self.mode_0_h_blank_first_tcycle = 369;
+                self.fetcher.tile_x_pos = 0;
                 self.fetcher.finished_sprites_in_scanline = false;
                 //clear it every scanline
                 self.sprites.clear();
@@ -956,6 +1018,9 @@ impl Ppu {
                 //reset fifos
                 self.sprite_fifo.data.clear();
                 self.bg_win_fifo.data.clear();
+                // a fetch cut off when the last line filled up belongs to that line, start this one fresh
+                self.fetcher.need_to_resume = false;
+                self.fetcher.current_step = 0;
                 self.fetcher.tcycle_budget = 0;
                 //self.fetcher.tile_x_coord = 0;
                 // always reset the layer before we start
@@ -1015,6 +1080,7 @@ impl Ppu {
 
                 // Mode 0 is the remainder of the dots left in the scan line (final dot is 456)
                 self.set_stat_ppu_mode(mbc, PPUMode::Mode_0_H_Blank);
+                mbc.start_hblank_hdma();
                 self.started_mode_0_in_scanline = true;
             }
             if self.tcycle_in_scanline >=  self.mode_0_h_blank_first_tcycle && self.started_mode_2_in_scanline && self.started_mode_3_in_scanline && self.started_mode_0_in_scanline && !self.started_mode_1_in_frame {
@@ -1072,7 +1138,10 @@ impl Ppu {
         }
         // max ly is 153 because there are 153 scanlines
         let max_ly_value = 153;
+        // wrapping ly also zeroes tcycle_in_frame, so the frame has to be flagged here or the check below never sees it
+        let mut is_end_of_frame = false;
         if mbc.hw_reg.ly > max_ly_value {
+            is_end_of_frame = true;
             mbc.hw_reg.ly = 0;
             self.tcycle_in_frame = 0;
             self.started_mode_2_in_scanline = false;
@@ -1097,7 +1166,7 @@ impl Ppu {
         //     self.tcycle_in_mode_3_draw = 0;
         // }
 
-        if self.tcycle_in_frame >= max_tcycle_in_frame {
+        if is_end_of_frame || self.tcycle_in_frame >= max_tcycle_in_frame {
             //print!("tcycle_in_frame is >= 70224, generating frame \n");
             self.tcycle_in_frame = 0;
             //self.tcycle_in_scanline = 0;
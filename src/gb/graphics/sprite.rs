@@ -6,6 +6,8 @@ pub struct Sprite {
     pub byte3_sprite_flags: u8,
 }
 
+crate::impl_state!(Sprite { byte0_y_pos, byte1_x_pos, byte2_tile_num, byte3_sprite_flags });
+
 
 
 impl Sprite {
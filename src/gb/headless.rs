@@ -0,0 +1,121 @@
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+use crate::gb::bios::ColorMode;
+use crate::gb::emu::Emu;
+use crate::gb::graphics::ppu::{PPUEvent, RenderState};
+use crate::gb::mbc::OpSource;
+use crate::gb::screenshot::{ScreenshotSource, save_screenshot};
+
+// runs the emu without windows or pacing, as fast as the host allows
+// test rom runners drive this and look at the cpu, memory and frame buffer themselves
+
+pub const GAME_WIDTH: u32 = 160;
+pub const GAME_HEIGHT: u32 = 144;
+pub const GAME_BUFFER_SIZE: usize = 92_160; // 160x144 rgba
+const LD_B_B: u8 = 0x40;
+
+pub struct Headless {
+    pub emu: Emu,
+    pub tile_buffer: Arc<Mutex<Vec<u8>>>,
+    pub bg_map_buffer: Arc<Mutex<Vec<u8>>>,
+    pub game_buffer: Arc<Mutex<Vec<u8>>>,
+    pub frames: u64,
+}
+
+impl Headless {
+    // the boot rom runs first, same as with a window
+    // the cart's header picks dmg or cgb
+    pub fn new(rom_file: &str) -> Self {
+        Headless::with_color_mode(rom_file, None)
+    }
+
+    // Some(ColorMode::Color) runs a dmg cart on a cgb, in compatibility mode
+    pub fn with_color_mode(rom_file: &str, color_mode: Option<ColorMode>) -> Self {
+        let mut emu = Emu::new(color_mode, true);
+        emu.load_rom_file(String::from(rom_file));
+        Headless {
+            emu,
+            tile_buffer: Arc::new(Mutex::new(vec![0u8; 65_536])),
+            bg_map_buffer: Arc::new(Mutex::new(vec![0u8; 262_144])),
+            game_buffer: Arc::new(Mutex::new(vec![0u8; GAME_BUFFER_SIZE])),
+            frames: 0,
+        }
+    }
+
+    pub fn mcycles(&self) -> u64 {
+        self.emu.cpu.total_mcycles
+    }
+
+    // one instruction, returns true when it finished a frame
+    pub fn step(&mut self) -> bool {
+        let event = self.emu.step(&self.tile_buffer, &self.bg_map_buffer, &self.game_buffer);
+        if event == PPUEvent::RenderEvent(RenderState::Render) {
+            self.frames += 1;
+            return true;
+        }
+        false
+    }
+
+    // steps until is_done says so or max_mcycles have run, false means it timed out
+    pub fn run_until(&mut self, max_mcycles: u64, mut is_done: impl FnMut(&Emu) -> bool) -> bool {
+        let start = self.mcycles();
+        while self.mcycles().wrapping_sub(start) < max_mcycles {
+            if is_done(&self.emu) {
+                return true;
+            }
+            let before = self.mcycles();
+            self.step();
+            // a locked up cpu still ticks, but a paused debugger doesn't, don't spin forever on it
+            if self.mcycles() == before {
+                return false;
+            }
+        }
+        is_done(&self.emu)
+    }
+
+    // false if the emu stopped making progress first
+    pub fn run_frames(&mut self, frames: u64) -> bool {
+        let target = self.frames + frames;
+        while self.frames < target {
+            let before = self.mcycles();
+            self.step();
+            if self.mcycles() == before {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn copy_game_buffer(&self) -> Vec<u8> {
+        self.game_buffer.lock().unwrap().clone()
+    }
+
+    // 256x224, only drawn with ColorMode::Super
+    pub fn copy_sgb_buffer(&self) -> Vec<u8> {
+        self.emu.sgb_buffer.lock().unwrap().clone()
+    }
+
+    pub fn screenshot(&self, source: ScreenshotSource, scale: u32) -> std::io::Result<String> {
+        let buffer = match source {
+            ScreenshotSource::Game => &self.game_buffer,
+            ScreenshotSource::Tiles => &self.tile_buffer,
+            ScreenshotSource::BGMap => &self.bg_map_buffer,
+        };
+        save_screenshot(source, &buffer.lock().unwrap(), scale)
+    }
+}
+
+// test roms use LD B,B as a software breakpoint to say they're done
+// only counts once the boot rom is gone, it never runs one
+pub fn is_at_ld_b_b(emu: &Emu) -> bool {
+    let pc = emu.cpu.registers.get_pc();
+    emu.mbc.hw_reg.boot_rom_control != 0 && emu.mbc.read(pc, OpSource::Debugger) == LD_B_B
+}
+
+// test rom runners catch panics so one unsupported rom doesn't take the rest down with it
+pub fn panic_message(e: Box<dyn Any + Send>) -> String {
+    e.downcast_ref::<String>().map(|s| s.trim().to_string())
+        .or_else(|| e.downcast_ref::<&str>().map(|s| s.trim().to_string()))
+        .unwrap_or_else(|| String::from("unknown panic"))
+}
@@ -22,7 +22,7 @@ pub struct HardwareRegisters {
     pub ie: u8,               // FFFF
 
     // Joypad and serial
-    pub joyp: u8, // FF00
+    // FF00 is mbc.joypad, it's computed from the buttons on every read
     pub sb: u8,   // FF01
     pub sc: u8,   // FF02
 
@@ -60,8 +60,35 @@ pub struct HardwareRegisters {
 
     // Wave pattern RAM
     pub wave_pattern: [u8; 16], // FF30–FF3F
+
+    // CGB only, a DMG or a CGB in compatibility mode reads them as FF and ignores writes
+    pub key0: u8, // FF4C, only the boot rom can write it, bit 2 is dmg compatibility mode
+    pub key1: u8, // FF4D, bit 7 is double speed, bit 0 arms a switch on the next STOP
+    pub vbk: u8,  // FF4F, vram bank
+    pub rp: u8,   // FF56, infrared port, nothing is ever on the other end
+    pub opri: u8, // FF6C, object priority, 0 is by oam index like the cgb
+    pub svbk: u8, // FF70, wram bank for D000-DFFF, 0 means 1
+    pub undocumented: [u8; 4], // FF72-FF75, FF72 and FF73 even exist in compatibility mode
+    pub hdma1: u8, // FF51, source high, write only
+    pub hdma2: u8, // FF52, source low, the low 4 bits are ignored
+    pub hdma3: u8, // FF53, vram destination high, only bits 0-4 count
+    pub hdma4: u8, // FF54, vram destination low, the low 4 bits are ignored
+    pub hdma5: u8, // FF55, 16 byte blocks left minus one, bit 7 clear while a transfer runs, FF when done
+
+    // CGB palettes, 8 of 4 colors each, RGB555 little endian
+    pub bcps: u8, // FF68, bits 0-5 index BCPD into bg_palette_ram, bit 7 increments it after every write
+    pub ocps: u8, // FF6A, the same for OCPD
+    pub bg_palette_ram: [u8; 64],  // read and written through FF69 BCPD
+    pub obj_palette_ram: [u8; 64], // read and written through FF6B OCPD
 }
 
+crate::impl_state!(HardwareRegisters {
+    lcdc, stat, scy, scx, ly, lyc, dma, bgp, obp0, obp1, wy, wx, boot_rom_control, ie, sb, sc, div, tima, tma,
+    tac, interrupt_flags, nr10, nr11, nr12, nr13, nr14, nr21, nr22, nr23, nr24, nr30, nr31, nr32, nr33, nr34,
+    nr41, nr42, nr43, nr44, nr50, nr51, nr52, wave_pattern, key0, key1, vbk, rp, opri, svbk, undocumented,
+    hdma1, hdma2, hdma3, hdma4, hdma5, bcps, ocps, bg_palette_ram, obj_palette_ram,
+});
+
 impl HardwareRegisters {
     pub fn new() -> Self {
         HardwareRegisters {
@@ -81,7 +108,6 @@ impl HardwareRegisters {
             boot_rom_control: 0,
             ie: 0,
 
-            joyp: 0xFF,
             sb: 0,
             sc: 0,
 
@@ -115,9 +141,46 @@ impl HardwareRegisters {
             nr52: 0,
 
             wave_pattern: [0; 16],
+
+            key0: 0,
+            key1: 0,
+            vbk: 0,
+            rp: 0,
+            opri: 0,
+            svbk: 0,
+            undocumented: [0; 4],
+            hdma1: 0,
+            hdma2: 0,
+            hdma3: 0,
+            hdma4: 0,
+            hdma5: 0xFF,
+
+            bcps: 0,
+            ocps: 0,
+            bg_palette_ram: [0; 64],
+            obj_palette_ram: [0; 64],
+        }
+    }
+
+    // BCPD/OCPD, the index moves on even when mode 3 keeps the write from landing
+    pub fn write_palette_data(spec: &mut u8, palette_ram: &mut [u8; 64], byte: u8, is_locked: bool) {
+        if !is_locked {
+            palette_ram[(*spec & 0b0011_1111) as usize] = byte;
+        }
+        if *spec & 0b1000_0000 != 0 {
+            *spec = 0b1000_0000 | (spec.wrapping_add(1) & 0b0011_1111);
         }
     }
 
+    pub fn is_double_speed(&self) -> bool {
+        self.key1 & 0b1000_0000 != 0
+    }
+
+    // 1-7, SVBK 0 picks bank 1 too
+    pub fn get_wram_bank(&self) -> u8 {
+        (self.svbk & 0b0000_0111).max(1)
+    }
+
     pub fn set_stat_ppu_mode_bit0_1(&mut self) {
         self.stat |= 0b0000_0011;
     }
@@ -212,6 +275,11 @@ impl HardwareRegisters {
         if self.lcdc & 0b0000_0100 == 0b0000_0100 { true } else { false }
     }
 
+    // on a cgb this is bg and window master priority instead of bg enable
+    pub fn is_lcdc_bg_win_enable_priority_bit0_enabled(&self) -> bool {
+        if self.lcdc & 0b0000_0001 == 0b0000_0001 { true } else { false }
+    }
+
 
     // check both ie and if
     pub fn is_vblank_bit0_interrupt_requested_and_enabled(&self) -> bool {
@@ -1,208 +1,87 @@
 
 
-use winit::keyboard::KeyCode;
-use crate::gb::mbc::Mbc;
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Button {
+    Right,
+    Left,
+    Up,
+    Down,
+    A,
+    B,
+    Select,
+    Start,
+}
 
-use winit::event::ElementState;
+impl Button {
+    // one bit per button, a u8 holds everything held on a frame
+    pub fn get_bit(&self) -> u8 {
+        match self {
+            Button::Right => 0x01,
+            Button::Left => 0x02,
+            Button::Up => 0x04,
+            Button::Down => 0x08,
+            Button::A => 0x10,
+            Button::B => 0x20,
+            Button::Select => 0x40,
+            Button::Start => 0x80,
+        }
+    }
 
+}
+
+// the buttons sit in a 2x4 matrix, JOYP bits 4 and 5 pick the rows and bits 0-3 read the lines
+// a line reads 0 when a held button is on a selected row, with both rows selected either one pulls it low
+// the dpad and button bits of Button are already in line order, right/a on bit 0 to down/start on bit 3
 pub struct Joypad {
-    pub a_right: bool,
-    pub b_left: bool,
-    pub select_up: bool,
-    pub start_down: bool,
-    pub select_dpad: bool,
-    pub select_buttons: bool,
-    pub is_reg_pending_update_from_obj: bool,
-    pub is_pending_joypad_interrupt_trigger: bool,
+    pub buttons: u8, // Button bits, only changes between frames
+    pub select: u8, // JOYP bits 4-5 as last written, 0 selects the row
 }
 
+crate::impl_state!(Joypad { buttons, select });
+
 impl Joypad {
     pub fn new() -> Self {
         Joypad {
-            a_right: false,
-            b_left: false,
-            select_up: false,
-            start_down: false,
-            select_dpad: false,
-            select_buttons: false,
-            is_reg_pending_update_from_obj: false,
-            is_pending_joypad_interrupt_trigger: false,
+            buttons: 0,
+            select: 0x30,
         }
     }
 
-    pub fn sync_state (&mut self, mbc: &mut Mbc) {
-        if self.is_pending_joypad_interrupt_trigger {
-            print!("executing mbc.hw_reg.set_if_joypad_bit4()\n");
-            mbc.hw_reg.set_if_joypad_bit4();
-            //mbc.hw_reg.set_ie_joypad_bit4();
-            self.is_pending_joypad_interrupt_trigger = false;
-        }
-
-        if self.is_reg_pending_update_from_obj {
-            println!("updating joypad reg from obj");
-            let lower_joyp = self.get_state_as_u8();
-            let upper_joyp =  mbc.hw_reg.joyp & 0b0011_0000;
-            mbc.hw_reg.joyp = upper_joyp | lower_joyp;
-            self.is_reg_pending_update_from_obj = false;
+    // the lower nibble of JOYP, 1 is released
+    pub fn get_lines(&self) -> u8 {
+        let mut pressed = 0;
+        if self.select & 0x10 == 0 {
+            pressed |= self.buttons & 0x0F;
         }
-
-        else if mbc.is_joypad_pending_update_from_reg {
-            println!("updating joypad from reg");
-
-            let byte = mbc.hw_reg.joyp;
-            if byte & 0x30 == 0x30 {
-                self.select_buttons = false;
-                self.select_dpad = false;
-                self.a_right = false;
-                self.b_left = false;
-                self.select_up = false;
-                self.start_down = false;
-            }
-            else if byte & 0x20 == 0x00 {
-                self.select_buttons = true;
-                self.select_dpad = false;
-
-            }
-            else if byte & 0x10 == 0x00 {
-                self.select_dpad = true;
-                self.select_buttons = false;
-            }
-
-
-            mbc.is_joypad_pending_update_from_reg = false;
+        if self.select & 0x20 == 0 {
+            pressed |= self.buttons >> 4;
         }
-
+        !pressed & 0x0F
     }
 
-    // todo handle key press VS release
-    pub fn handle_input(&mut self, key: KeyCode, state: ElementState) {
-        // interrupt only triggered when bit goes from 1 to 0 (key pressed)
-        if state == ElementState::Pressed {
-            println!("ElementState is Pressed, setting is_pending_joypad_interrupt_trigger to true");
-            self.is_pending_joypad_interrupt_trigger = true;
-
-            match key {
-                KeyCode::KeyW => {
-                    self.select_up = true;
-                    println!("pressed select or up");
-                },
-                KeyCode::KeyA => {
-                    self.b_left = true;
-                    println!("pressed b or left");
-                },
-                KeyCode::KeyS => {
-                    self.start_down = true;
-                    println!("pressed start or down");
-                },
-                KeyCode::KeyD => {
-                    self.a_right = true;
-                    println!("pressed a or right");
-                },
-                KeyCode::Backspace => {
-                    self.select_up = true;
-                    println!("pressed select or up");
-                },
-                KeyCode::Enter => {
-                    self.start_down = true;
-                    println!("pressed start or down");
-                },
-                _ => {
-                    println!("unrecognized key in Joypad.handle_input()");
-                }
-            }
-
-            // just for debugging
-            if self.select_buttons {
-                if self.select_up == true {
-                    println!("pressed up on dpad");
-                }
-                if self.b_left == true {
-                    println!("pressed left on dpad");
-                }
-                if self.start_down == true {
-                    println!("pressed down on dpad");
-                }
-                if self.a_right == true {
-                    println!("pressed right on dpad");
-                }
-
-            }
-            else if self.select_dpad {
-                if self.select_up == true {
-                    println!("pressed select button");
-                }
-                if self.b_left == true {
-                    println!("pressed b button");
-                }
-                if self.start_down == true {
-                    println!("pressed start button");
-                }
-                if self.a_right == true {
-                    println!("pressed a button");
-                }
-            }
-        }
-        else { // release key
-            match key {
-                KeyCode::KeyW => {
-                    self.select_up = false;
-                    println!("released select or up");
-                },
-                KeyCode::KeyA => {
-                    self.b_left = false;
-                    println!("released b or left");
-                },
-                KeyCode::KeyS => {
-                    self.start_down = false;
-                    println!("released start or down");
-
-                },
-                KeyCode::KeyD => {
-                    self.a_right = false;
-                    println!("released a or right");
-                },
-                KeyCode::Backspace => {
-                    self.select_up = false;
-                    println!("released select or up");
-                },
-                KeyCode::Enter => {
-                    self.start_down = false;
-                    println!("released start or down");
-                },
-                _ => {
-                    println!("unrecognized released key in Joypad.handle_input()");
-                }
-            }
-        }
-
-        self.is_reg_pending_update_from_obj = true;
-
+    // bits 6-7 aren't there and read 1
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | self.get_lines()
     }
 
-    pub fn get_state_as_u8(&self) -> u8 {
-        let mut state: u8 = 0xFF;
-        if self.select_dpad || self.select_buttons {
-            // if self.select_dpad == true {
-            //     state &= 0b1110_1111;
-            // }
-            // else if self.select_buttons == true {
-            //     state &= 0b1101_1111;
-            // }
+    // these return true when a line went from high to low, that's what requests the joypad interrupt
+    // selecting a row with a button already held counts as well
+    pub fn write(&mut self, byte: u8) -> bool {
+        let before = self.get_lines();
+        self.select = byte & 0x30;
+        before & !self.get_lines() != 0
+    }
 
-            if self.a_right == true {
-                state &= 0b1111_1110;
-            }
-            if self.b_left == true {
-                state &= 0b1111_1101;
-            }
-            if self.select_up == true {
-                state &= 0b1111_1011;
-            }
-            if self.start_down == true {
-                state &= 0b1111_0111
-            }
-        }
+    // called by the emu at the end of every frame with what's held for the next one
+    pub fn set_buttons(&mut self, buttons: u8) -> bool {
+        let before = self.get_lines();
+        self.buttons = buttons;
+        before & !self.get_lines() != 0
+    }
+}
 
-        state
+impl Default for Joypad {
+    fn default() -> Self {
+        Joypad::new()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::time::SystemTime;
+
+use serde::Deserialize;
+use winit::keyboard::KeyCode;
+
+use crate::gb::joypad::Button;
+
+// which keyboard keys press which buttons and hotkeys, loaded from a json file
+// every action takes a list of winit KeyCode names, anything left out keeps its default:
+//   {
+//     "buttons": { "a": ["KeyK", "KeyX"], "b": ["KeyJ"], "up": ["KeyW", "ArrowUp"] },
+//     "hotkeys": { "pause": ["KeyP"], "fast_forward": ["Tab"] }
+//   }
+// the file is checked for changes while the emu runs, so edits apply without a restart
+
+pub const DEFAULT_BINDINGS_FILE: &str = "keybindings.json";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hotkey {
+    Pause,
+    FrameAdvance, // pauses if it isn't already
+    Reset, // soft
+    HardReset,
+    SaveState,
+    LoadState,
+    FastForward, // only while held
+    Screenshot,
+    RecordVideo,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyAction {
+    Button(Button),
+    Hotkey(Hotkey),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ButtonKeys {
+    up: Vec<KeyCode>,
+    down: Vec<KeyCode>,
+    left: Vec<KeyCode>,
+    right: Vec<KeyCode>,
+    a: Vec<KeyCode>,
+    b: Vec<KeyCode>,
+    select: Vec<KeyCode>,
+    start: Vec<KeyCode>,
+}
+
+impl Default for ButtonKeys {
+    fn default() -> Self {
+        ButtonKeys {
+            up: vec![KeyCode::KeyW],
+            down: vec![KeyCode::KeyS],
+            left: vec![KeyCode::KeyA],
+            right: vec![KeyCode::KeyD],
+            a: vec![KeyCode::KeyK],
+            b: vec![KeyCode::KeyJ],
+            select: vec![KeyCode::Backspace],
+            start: vec![KeyCode::Enter],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HotkeyKeys {
+    pause: Vec<KeyCode>,
+    frame_advance: Vec<KeyCode>,
+    reset: Vec<KeyCode>,
+    hard_reset: Vec<KeyCode>,
+    save_state: Vec<KeyCode>,
+    load_state: Vec<KeyCode>,
+    fast_forward: Vec<KeyCode>,
+    screenshot: Vec<KeyCode>,
+    record_video: Vec<KeyCode>,
+}
+
+impl Default for HotkeyKeys {
+    fn default() -> Self {
+        HotkeyKeys {
+            pause: vec![KeyCode::KeyP],
+            frame_advance: vec![KeyCode::KeyN],
+            reset: vec![KeyCode::F2],
+            hard_reset: vec![KeyCode::F3],
+            save_state: vec![KeyCode::F5],
+            load_state: vec![KeyCode::F8],
+            fast_forward: vec![KeyCode::Tab],
+            screenshot: vec![KeyCode::F12],
+            record_video: vec![KeyCode::F9],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeyBindingsFile {
+    buttons: ButtonKeys,
+    hotkeys: HotkeyKeys,
+}
+
+impl KeyBindingsFile {
+    fn get_bindings(&self) -> [(&Vec<KeyCode>, KeyAction); 17] {
+        let b = &self.buttons;
+        let h = &self.hotkeys;
+        [
+            (&b.up, KeyAction::Button(Button::Up)),
+            (&b.down, KeyAction::Button(Button::Down)),
+            (&b.left, KeyAction::Button(Button::Left)),
+            (&b.right, KeyAction::Button(Button::Right)),
+            (&b.a, KeyAction::Button(Button::A)),
+            (&b.b, KeyAction::Button(Button::B)),
+            (&b.select, KeyAction::Button(Button::Select)),
+            (&b.start, KeyAction::Button(Button::Start)),
+            (&h.pause, KeyAction::Hotkey(Hotkey::Pause)),
+            (&h.frame_advance, KeyAction::Hotkey(Hotkey::FrameAdvance)),
+            (&h.reset, KeyAction::Hotkey(Hotkey::Reset)),
+            (&h.hard_reset, KeyAction::Hotkey(Hotkey::HardReset)),
+            (&h.save_state, KeyAction::Hotkey(Hotkey::SaveState)),
+            (&h.load_state, KeyAction::Hotkey(Hotkey::LoadState)),
+            (&h.fast_forward, KeyAction::Hotkey(Hotkey::FastForward)),
+            (&h.screenshot, KeyAction::Hotkey(Hotkey::Screenshot)),
+            (&h.record_video, KeyAction::Hotkey(Hotkey::RecordVideo)),
+        ]
+    }
+
+    // actions the file left alone go in first so the ones it sets win a shared key
+    fn get_actions(&self) -> HashMap<KeyCode, KeyAction> {
+        let defaults = KeyBindingsFile::default();
+        let (kept, changed): (Vec<_>, Vec<_>) = self.get_bindings().into_iter()
+            .zip(defaults.get_bindings())
+            .partition(|((keys, _), (default_keys, _))| keys == default_keys);
+        // the defaults don't share any keys
+        let default_actions: HashMap<KeyCode, KeyAction> = kept.into_iter()
+            .flat_map(|((keys, action), _)| keys.iter().map(move |&key| (key, action)))
+            .collect();
+        let mut actions = default_actions.clone();
+        for ((keys, action), _) in changed {
+            for &key in keys {
+                match actions.insert(key, action) {
+                    Some(previous) if default_actions.get(&key) == Some(&previous) => {
+                        println!("{:?} is bound to {:?} by default, the key bindings file makes it {:?}", key, previous, action);
+                    },
+                    Some(previous) => {
+                        println!("{:?} is bound to both {:?} and {:?}, using {:?}", key, previous, action, action);
+                    },
+                    None => {},
+                }
+            }
+        }
+        actions
+    }
+}
+
+pub struct KeyBindings {
+    pub file: Option<String>,
+    actions: HashMap<KeyCode, KeyAction>,
+    modified: Option<SystemTime>,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        KeyBindings {
+            file: None,
+            actions: KeyBindingsFile::default().get_actions(),
+            modified: None,
+        }
+    }
+
+    pub fn load(file: &str) -> io::Result<Self> {
+        let mut bindings = KeyBindings::new();
+        bindings.file = Some(String::from(file));
+        bindings.reload()?;
+        Ok(bindings)
+    }
+
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let parsed: KeyBindingsFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(KeyBindings {
+            file: None,
+            actions: parsed.get_actions(),
+            modified: None,
+        })
+    }
+
+    pub fn get_action(&self, key: KeyCode) -> Option<KeyAction> {
+        self.actions.get(&key).copied()
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let Some(file) = self.file.as_ref() else { return Ok(()); };
+        self.modified = fs::metadata(file).and_then(|m| m.modified()).ok();
+        let parsed = KeyBindings::parse(&fs::read_to_string(file)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file, e)))?;
+        self.actions = parsed.actions;
+        Ok(())
+    }
+
+    // a broken edit keeps the old bindings, the next save gets another try
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(file) = self.file.as_ref() else { return false; };
+        let modified = fs::metadata(file).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        let file = file.clone();
+        match self.reload() {
+            Ok(()) => {
+                println!("reloaded key bindings from {}", file);
+                true
+            },
+            Err(e) => {
+                println!("unable to reload key bindings, keeping the old ones: {}", e);
+                false
+            },
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings::new()
+    }
+}
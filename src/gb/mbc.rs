@@ -1,21 +1,27 @@
 use std::cmp::PartialEq;
 use crate::gb::ram::*;
 use crate::gb::rom::*;
+use crate::gb::debugger::{Watchpoint, WatchHit, WatchKind};
 use crate::gb::bios::*;
 use crate::gb::hwregisters::HardwareRegisters;
 
 use std::thread::sleep;
 use std::time::Duration;
 use crate::gb::joypad::Joypad;
+use crate::gb::sgb::Sgb;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum OpSource {
     CPU,
     PPU,
+    DMA,
+    Debugger, // peeks that shouldn't disturb or be blocked like the cpu
 }
 
 pub const ROM_BANK_SIZE: u16 = 0x4000;
 pub const RAM_BANK_SIZE: u16 = 0x4000;
+// games that talk over the link cable would grow serial_out forever, test roms print far less than this
+pub const SERIAL_OUT_LIMIT: usize = 0x4000;
 
 pub struct Mbc {
     pub hw_reg: HardwareRegisters,
@@ -27,6 +33,7 @@ pub struct Mbc {
     ram_bank: u8,
     wr_ram_bank: bool,
     pub rom_ram: RomRam,
+    rtc_registers: [u8; 5], // mbc3's clock, S M H DL DH. it keeps what's written but doesn't count yet
     //pub need_tile_update: bool,
     //pub need_bg_map_update: bool,
     pub rom_bank_mode: RomBankMode,
@@ -37,13 +44,36 @@ pub struct Mbc {
     io: Ram,
     hram: Ram,
     pub restrict_vram_access: bool,
-    pub reset_cpu_counter: bool,
-    pub dma_active: bool,
-    pub dma_cycles_remaining: u64,
+    pub div_counter: u16, // internal 16 bit divider, DIV is the upper 8 bits
+    pub dma_active: bool, // a transfer is running and owns OAM and the source bus
+    pub dma_source: u16,
+    pub dma_byte_idx: u16,
+    pub dma_last_byte: u8, // what the cpu sees when it reads the bus dma is using
+    pub dma_start_delay: u8, // mcycles until a requested transfer starts, 0 if none is pending
+    pub dma_pending_source: u16,
     pub is_testing_enabled: bool,
-    pub is_joypad_pending_update_from_reg: bool,
+    pub joypad: Joypad,
+    pub watchpoints: Vec<Watchpoint>,
+    pub watch_hit: Option<WatchHit>, // the debugger picks this up after the instruction finishes
+    pub is_ly_stubbed: bool, // LY always reads 0x90 like gameboy doctor's reference logs expect
+    pub serial_out: Vec<u8>, // the last bytes sent over the link cable, test roms print their results this way
+    pub is_cgb: bool, // the hardware, a dmg cart on a cgb still runs in compatibility mode
+    pub is_ppu_mcycle_skipped: bool, // in double speed the ppu only sees every other mcycle
+    pub hdma_source: u16,
+    pub hdma_dest: u16, // offset into vram
+    pub hdma_is_hblank: bool, // an HBlank transfer is running, the ppu starts a block every mode 0
+    pub hdma_bytes_left: u16, // bytes still to copy right now, the cpu is halted until it's 0
+    pub sgb: Option<Sgb>, // set when the dmg sits in a super game boy, it listens to JOYP
 }
 
+// the cart and boot rom, the cpu test ram and the debugging bits stay with the emu loading a state
+crate::impl_state!(Mbc {
+    hw_reg, ram, rom_bank, ram_bank, wr_ram_bank, rom_ram, rtc_registers, rom_bank_mode, xram, vram, wram,
+    oam, io, hram, restrict_vram_access, div_counter, dma_active, dma_source, dma_byte_idx, dma_last_byte,
+    dma_start_delay, dma_pending_source, joypad, is_cgb, is_ppu_mcycle_skipped, hdma_source, hdma_dest,
+    hdma_is_hblank, hdma_bytes_left, sgb,
+});
+
 
 impl Mbc {
 
@@ -54,10 +84,11 @@ impl Mbc {
             test_ram: Ram::new(0x00),
             boot_rom: Ram::new(0x00),
             rom: None,
-            rom_bank: 0,
+            rom_bank: 1, // mbc1 and mbc3 power up with bank 1 at 4000-7FFF
             ram_bank: 0,
             wr_ram_bank: false,
             rom_ram: RomRam::new(),
+            rtc_registers: [0; 5],
             // need_tile_update: false,
             // need_bg_map_update: false,
             rom_bank_mode: RomBankMode::Simple,
@@ -68,14 +99,182 @@ impl Mbc {
             io: Ram::new(0xFF),
             hram: Ram::new(0xFF),
             restrict_vram_access: false,
-            reset_cpu_counter: false,
+            div_counter: 0,
             dma_active: false,
-            dma_cycles_remaining: 0,
+            dma_source: 0,
+            dma_byte_idx: 0,
+            dma_last_byte: 0xFF,
+            dma_start_delay: 0,
+            dma_pending_source: 0,
             is_testing_enabled: false,
-            is_joypad_pending_update_from_reg: false,
+            joypad: Joypad::new(),
+            watchpoints: Vec::new(),
+            watch_hit: None,
+            is_ly_stubbed: false,
+            serial_out: Vec::new(),
+            is_cgb: false,
+            is_ppu_mcycle_skipped: false,
+            hdma_source: 0,
+            hdma_dest: 0,
+            hdma_is_hblank: false,
+            hdma_bytes_left: 0,
+            sgb: None,
+        }
+    }
+
+    // back to power-on, the cartridge stays plugged in so the rom and its ram survive
+    // keep_memory leaves wram, vram, oam and hram as they are, like a reset that doesn't cut the power
+    pub fn reset(&mut self, keep_memory: bool) {
+        let mut mbc = Mbc::new();
+        mbc.rom = self.rom.take();
+        mbc.boot_rom = std::mem::replace(&mut self.boot_rom, Ram::new(0x00));
+        mbc.xram = std::mem::replace(&mut self.xram, Ram::new(0xFF));
+        mbc.rom_ram = std::mem::replace(&mut self.rom_ram, RomRam::new());
+        mbc.is_testing_enabled = self.is_testing_enabled;
+        mbc.is_cgb = self.is_cgb;
+        mbc.sgb = self.sgb.as_ref().map(|sgb| Sgb::new(sgb.is_enabled));
+        mbc.watchpoints = std::mem::take(&mut self.watchpoints);
+        if keep_memory {
+            mbc.wram = std::mem::replace(&mut self.wram, Ram::new(0xFF));
+            mbc.vram = std::mem::replace(&mut self.vram, Ram::new(0x00));
+            mbc.oam = std::mem::replace(&mut self.oam, Ram::new(0xFF));
+            mbc.hram = std::mem::replace(&mut self.hram, Ram::new(0xFF));
+        }
+        *self = mbc;
+    }
+
+    // takes the cartridge out, its ram goes with it
+    pub fn eject(&mut self) -> Option<Rom> {
+        self.xram = Ram::new(0xFF);
+        self.rom_ram = RomRam::new();
+        self.rom.take()
+    }
+
+    // the boot rom always has the cgb registers, after it's done KEY0 says whether the cart does
+    pub fn is_cgb_mode(&self) -> bool {
+        self.is_cgb && (self.hw_reg.boot_rom_control == 0 || self.hw_reg.key0 & 0b0000_0100 == 0)
+    }
+
+    pub fn get_vram_bank(&self) -> u8 {
+        if self.is_cgb_mode() { self.hw_reg.vbk & 0b0000_0001 } else { 0 }
+    }
+
+    // C000-CFFF is always bank 0, D000-DFFF is bank 1 unless SVBK picks another
+    pub fn get_wram_bank(&self) -> u8 {
+        if self.is_cgb_mode() { self.hw_reg.get_wram_bank() } else { 1 }
+    }
+
+    // vram is two 8KB banks back to back
+    fn get_vram_index(&self, address: u16) -> u16 {
+        (self.get_vram_bank() as u16) * 0x2000 + (address - 0x8000)
+    }
+
+    // wram is eight 4KB banks back to back, on a dmg that's just the first two
+    fn get_wram_index(&self, address: u16) -> u16 {
+        if address < 0xD000 {
+            address - 0xC000
+        } else {
+            (self.get_wram_bank() as u16) * 0x1000 + (address - 0xD000)
         }
     }
 
+    // the ppu picks its bank per fetch (tile attributes are in bank 1), whatever VBK says
+    pub fn read_vram(&self, bank: u8, address: u16) -> u8 {
+        self.vram.read((bank as u16 & 1) * 0x2000 + (address - 0x8000))
+    }
+
+    // STOP with KEY1 bit 0 set switches speed and resets the divider, false if it wasn't armed
+    // the real switch also stalls the cpu for ~2050 mcycles, that isn't modelled
+    pub fn switch_speed(&mut self) -> bool {
+        if !self.is_cgb_mode() || self.hw_reg.key1 & 0b0000_0001 == 0 {
+            return false;
+        }
+        self.hw_reg.key1 = (self.hw_reg.key1 ^ 0b1000_0000) & 0b1000_0000;
+        self.div_counter = 0;
+        self.hw_reg.div = 0;
+        true
+    }
+
+    // the bits that don't exist read as 1
+    fn read_cgb_reg(&self, address: u16, op_src: OpSource) -> u8 {
+        if !self.is_cgb {
+            return 0xFF;
+        }
+        let hw_reg = &self.hw_reg;
+        match address {
+            // these are there in compatibility mode too
+            0xFF4C if hw_reg.boot_rom_control == 0 => hw_reg.key0,
+            0xFF72 | 0xFF73 => hw_reg.undocumented[(address - 0xFF72) as usize],
+            0xFF75 => 0b1000_1111 | hw_reg.undocumented[3],
+            // PCM12 and PCM34, there's no apu so every channel is silent
+            0xFF76 | 0xFF77 => 0x00,
+            _ if !self.is_cgb_mode() => 0xFF,
+            0xFF4D => 0b0111_1110 | hw_reg.key1,
+            0xFF4F => 0b1111_1110 | hw_reg.vbk,
+            // bit 1 low would be light coming in
+            0xFF56 => 0b0011_1110 | hw_reg.rp,
+            0xFF55 => hw_reg.hdma5,
+            0xFF68 => 0b0100_0000 | hw_reg.bcps,
+            0xFF6A => 0b0100_0000 | hw_reg.ocps,
+            // palette ram is the ppu's while it draws
+            0xFF69 | 0xFF6B if op_src == OpSource::CPU && self.restrict_vram_access => 0xFF,
+            0xFF69 => hw_reg.bg_palette_ram[(hw_reg.bcps & 0b0011_1111) as usize],
+            0xFF6B => hw_reg.obj_palette_ram[(hw_reg.ocps & 0b0011_1111) as usize],
+            0xFF6C => 0b1111_1110 | hw_reg.opri,
+            0xFF70 => 0b1111_1000 | hw_reg.svbk,
+            0xFF74 => hw_reg.undocumented[2],
+            _ => 0xFF,
+        }
+    }
+
+    fn write_cgb_reg(&mut self, address: u16, byte: u8, op_src: OpSource) {
+        if !self.is_cgb {
+            return;
+        }
+        let is_cgb_mode = self.is_cgb_mode();
+        let is_drawing = op_src == OpSource::CPU && self.restrict_vram_access;
+        let hw_reg = &mut self.hw_reg;
+        match address {
+            // locked once the boot rom is unmapped
+            0xFF4C if hw_reg.boot_rom_control == 0 => hw_reg.key0 = byte,
+            0xFF72 | 0xFF73 => hw_reg.undocumented[(address - 0xFF72) as usize] = byte,
+            0xFF75 => hw_reg.undocumented[3] = byte & 0b0111_0000,
+            _ if !is_cgb_mode => {},
+            0xFF4D => hw_reg.key1 = (hw_reg.key1 & 0b1000_0000) | (byte & 0b0000_0001),
+            0xFF4F => hw_reg.vbk = byte & 0b0000_0001,
+            0xFF56 => hw_reg.rp = byte & 0b1100_0001,
+            0xFF51..=0xFF54 => self.write_hdma_address(address, byte),
+            0xFF55 => self.write_hdma5(byte),
+            0xFF68 => hw_reg.bcps = byte & 0b1011_1111,
+            0xFF6A => hw_reg.ocps = byte & 0b1011_1111,
+            0xFF69 => HardwareRegisters::write_palette_data(&mut hw_reg.bcps, &mut hw_reg.bg_palette_ram, byte, is_drawing),
+            0xFF6B => HardwareRegisters::write_palette_data(&mut hw_reg.ocps, &mut hw_reg.obj_palette_ram, byte, is_drawing),
+            0xFF6C => hw_reg.opri = byte & 0b0000_0001,
+            0xFF70 => hw_reg.svbk = byte & 0b0000_0111,
+            0xFF74 => hw_reg.undocumented[2] = byte,
+            _ => {},
+        }
+    }
+
+    fn is_mbc3(&self) -> bool {
+        let rom_type = self.rom.as_ref().map(|rom| rom.get_rom_type()).unwrap_or(RomType::Rom_Only);
+        matches!(rom_type, RomType::MBC3 | RomType::MBC3_RAM | RomType::MBC3_RAM_BATT | RomType::MBC3_RAM_BATT_RTC | RomType::MBC3_BATT_RTC)
+    }
+
+    // the cart ram as the .sav has it, None if the cart has no battery to keep it
+    // mbc3 banks it out of rom_ram, everything else out of xram
+    pub fn get_save_ram(&self) -> Option<Vec<u8>> {
+        let rom = self.rom.as_ref().filter(|rom| rom.has_battery())?;
+        let ram: &[u8] = if self.is_mbc3() { &self.rom_ram.memory } else { &self.xram.memory };
+        Some(ram[..rom.get_ram_bytes().min(ram.len())].to_vec())
+    }
+
+    pub fn load_save_ram(&mut self, data: &[u8]) {
+        let ram: &mut [u8] = if self.is_mbc3() { &mut self.rom_ram.memory } else { &mut self.xram.memory };
+        let len = data.len().min(ram.len());
+        ram[..len].copy_from_slice(&data[..len]);
+    }
+
     pub fn load_rom_to_mem(&mut self) {
 
         if self.rom.is_none() {
@@ -105,7 +304,7 @@ impl Mbc {
         if clock_select == 0b00  {
             return 1 << 9
         } else if clock_select == 0b01  {
-            return 1 << 2
+            return 1 << 3
         } else if clock_select == 0b10  {
             return 1 << 5
         } else if clock_select == 0b11  {
@@ -122,6 +321,159 @@ impl Mbc {
         }
     }
 
+    // steps the divider by one mcycle (4 tcycles)
+    pub fn tick_timer(&mut self) {
+        let last_counter = self.div_counter;
+        self.div_counter = self.div_counter.wrapping_add(4);
+        self.hw_reg.div = (self.div_counter >> 8) as u8;
+        self.tick_tima_reg(last_counter, self.div_counter);
+    }
+
+    // tima increments on the falling edge of the divider bit selected by TAC
+    // this also catches the DIV write glitch since that can pull the bit low early
+    pub fn tick_tima_reg(&mut self, last_counter: u16, counter: u16) {
+        if !self.is_tac_bit2_enable_set() {
+            return;
+        }
+        let interesting_bit = self.get_tima_reg_interesting_bit();
+        if last_counter & interesting_bit != 0 && counter & interesting_bit == 0 {
+            let result = self.hw_reg.tima.overflowing_add(1);
+            if result.1 {
+                self.hw_reg.tima = self.hw_reg.tma;
+                self.hw_reg.set_if_timer_bit2();
+            } else {
+                self.hw_reg.tima = result.0;
+            }
+        }
+    }
+
+    // OAM DMA copies one byte per mcycle, 160 mcycles in total
+    // a write to FF46 spends one mcycle setting up before the first byte moves
+    // writing FF46 again mid transfer restarts it, the old one keeps going until the new one starts
+    // oam stays locked for the whole mcycle the last byte goes in and is let go when the next one starts
+    pub fn tick_dma(&mut self) {
+        if self.dma_active && self.dma_byte_idx == 160 {
+            self.dma_active = false;
+        }
+        if self.dma_start_delay > 0 {
+            self.dma_start_delay -= 1;
+            if self.dma_start_delay == 0 {
+                self.dma_source = self.dma_pending_source;
+                self.dma_byte_idx = 0;
+                self.dma_active = true;
+            }
+        }
+
+        if !self.dma_active {
+            return;
+        }
+
+        let mut src = self.dma_source + self.dma_byte_idx;
+        // E000 and up reads through echo ram
+        if src >= 0xE000 {
+            src -= 0x2000;
+        }
+        let val = self.read(src, OpSource::DMA);
+        self.oam.write(self.dma_byte_idx, val);
+        self.dma_last_byte = val;
+        self.dma_byte_idx += 1;
+    }
+
+    // HDMA copies 2 bytes per mcycle, 1 in double speed, so a 16 byte block takes as long as
+    // 8 single speed mcycles either way
+    pub fn tick_hdma(&mut self) {
+        let bytes_per_mcycle = if self.hw_reg.is_double_speed() { 1 } else { 2 };
+        for _ in 0..bytes_per_mcycle {
+            let val = self.read(self.hdma_source, OpSource::DMA);
+            self.write(0x8000 | self.hdma_dest, val, OpSource::DMA);
+            self.hdma_source = self.hdma_source.wrapping_add(1);
+            self.hdma_dest = (self.hdma_dest + 1) & 0x1FFF;
+            self.hdma_bytes_left -= 1;
+            if self.hdma_bytes_left % 16 == 0 {
+                // a block is done, HDMA5 counts down past 0 to FF
+                self.hw_reg.hdma5 = self.hw_reg.hdma5.wrapping_sub(1);
+                if self.hw_reg.hdma5 == 0xFF {
+                    self.hdma_is_hblank = false;
+                }
+            }
+            if self.hdma_bytes_left == 0 {
+                return;
+            }
+        }
+    }
+
+    // the ppu calls this when it enters mode 0
+    pub fn start_hblank_hdma(&mut self) {
+        if self.hdma_is_hblank && self.hdma_bytes_left == 0 {
+            self.hdma_bytes_left = 16;
+        }
+    }
+
+    // the source and destination counters only pick up HDMA1-4 when those are written
+    // a stopped transfer started again without rewriting them carries on where it left off
+    fn write_hdma_address(&mut self, address: u16, byte: u8) {
+        let hw_reg = &mut self.hw_reg;
+        match address {
+            0xFF51 => hw_reg.hdma1 = byte,
+            0xFF52 => hw_reg.hdma2 = byte,
+            0xFF53 => hw_reg.hdma3 = byte,
+            _ => hw_reg.hdma4 = byte,
+        }
+        match address {
+            0xFF51 | 0xFF52 => self.hdma_source = u16::from_be_bytes([hw_reg.hdma1, hw_reg.hdma2]) & 0xFFF0,
+            _ => self.hdma_dest = u16::from_be_bytes([hw_reg.hdma3, hw_reg.hdma4]) & 0x1FF0,
+        }
+    }
+
+    fn write_hdma5(&mut self, byte: u8) {
+        // bit 7 clear while an HBlank transfer runs stops it, HDMA5 keeps the blocks that are left
+        if self.hdma_is_hblank && byte & 0b1000_0000 == 0 {
+            self.hdma_is_hblank = false;
+            self.hw_reg.hdma5 |= 0b1000_0000;
+            return;
+        }
+        self.hw_reg.hdma5 = byte & 0b0111_1111;
+        if byte & 0b1000_0000 != 0 {
+            self.hdma_is_hblank = true;
+        } else {
+            // general purpose, everything in one go
+            self.hdma_bytes_left = ((byte & 0b0111_1111) as u16 + 1) * 16;
+        }
+    }
+
+    // 0 is the external bus (rom, xram, wram), 1 is the vram bus
+    pub fn get_dma_bus(address: u16) -> u8 {
+        if (0x8000..=0x9FFF).contains(&address) {
+            1
+        } else {
+            0
+        }
+    }
+
+    // while dma runs, the cpu only gets hram and io
+    // anything on the bus dma is reading from returns the byte being copied, writes there are lost
+    pub fn is_dma_bus_conflict(&self, address: u16) -> bool {
+        if !self.dma_active || address >= 0xFE00 {
+            return false;
+        }
+        Mbc::get_dma_bus(address) == Mbc::get_dma_bus(self.dma_source)
+    }
+
+    // which rom bank the cpu sees at this address, 0 for the fixed bank and anything outside rom
+    pub fn get_rom_bank(&self, address: u16) -> u8 {
+        if (0x4000..=0x7FFF).contains(&address) {
+            if self.rom_bank == 0 { 1 } else { self.rom_bank }
+        } else {
+            0
+        }
+    }
+
+    pub fn check_watchpoints(&mut self, address: u16, value: u8, kind: WatchKind) {
+        if self.watch_hit.is_none() && self.watchpoints.iter().any(|wp| wp.matches(address, kind)) {
+            self.watch_hit = Some(WatchHit { address, value, kind });
+        }
+    }
+
     pub fn read_rom(&self, address: u16, op_src: OpSource) -> u8 {
 
 
@@ -146,11 +498,12 @@ impl Mbc {
                 self.rom_only_read(address)
                 //self.ram.read(address)
             },
-            RomType::MBC1 => {
+            // the ram and battery only matter to what's in xram, the mapper is the same
+            RomType::MBC1 | RomType::MBC1_RAM | RomType::MBC1_RAM_BATT => {
                 //print!("read_rom MBC1\n");
                 self.mbc1_read(address)
             },
-            RomType::MBC3 => {
+            RomType::MBC3 | RomType::MBC3_RAM | RomType::MBC3_RAM_BATT | RomType::MBC3_RAM_BATT_RTC | RomType::MBC3_BATT_RTC => {
                 self.mbc3_read(address)
             },
             _ => {
@@ -171,6 +524,15 @@ impl Mbc {
         }
     }
 
+    // the mappers read() and write() know about, anything else panics on its first access
+    pub fn is_mapper_supported(rom_type: RomType) -> bool {
+        matches!(
+            rom_type,
+            RomType::Rom_Only | RomType::MBC1 | RomType::MBC1_RAM | RomType::MBC1_RAM_BATT
+                | RomType::MBC3 | RomType::MBC3_RAM | RomType::MBC3_RAM_BATT | RomType::MBC3_RAM_BATT_RTC | RomType::MBC3_BATT_RTC
+        )
+    }
+
     pub fn read(&self, address: u16, op_src: OpSource) -> u8 {
         // this is only for CPU testing, then it's turned off
         if self.is_testing_enabled {
@@ -178,6 +540,15 @@ impl Mbc {
         }
 
 
+        if op_src == OpSource::CPU && self.is_dma_bus_conflict(address) {
+            return self.dma_last_byte;
+        }
+
+        // OAM is locked by the transfer
+        if op_src != OpSource::DMA && self.dma_active && (0xFE00..=0xFE9F).contains(&address) {
+            return 0xFF;
+        }
+
         // handle special reads: BIOS, hw reg, then ROM
         match address {
             // BIOS
@@ -191,11 +562,15 @@ impl Mbc {
                     self.read_rom(address, op_src)
                 }
             },
+            // the cgb boot rom is 2304 bytes, the cart header shows through the gap at 100-1FF
+            0x200..=0x8FF if self.is_cgb && self.hw_reg.boot_rom_control == 0 => self.read_bios(address),
+
+            // the ppu isn't affected by VBK
+            0x8000..=0x9FFF if op_src == OpSource::PPU => self.read_vram(0, address),
             // Joypad and serial
-            //0xFF00 => self.hw_reg.joyp,
-            0xFF00 => {
-                //self.joypad.get_state()
-                self.hw_reg.joyp
+            0xFF00 => match self.sgb.as_ref() {
+                Some(sgb) => sgb.read_joyp(&self.joypad),
+                None => self.joypad.read(),
             },
 
             0xFF01 => self.hw_reg.sb,
@@ -215,6 +590,7 @@ impl Mbc {
             0xFF41 => self.hw_reg.stat,
             0xFF42 => self.hw_reg.scy,
             0xFF43 => self.hw_reg.scx,
+            0xFF44 if self.is_ly_stubbed => 0x90,
             0xFF44 => self.hw_reg.ly,
             0xFF45 => self.hw_reg.lyc,
             0xFF46 => {
@@ -262,6 +638,9 @@ impl Mbc {
                 self.hw_reg.wave_pattern[index]
             },
 
+            // CGB
+            0xFF4C | 0xFF4D | 0xFF4F | 0xFF51..=0xFF56 | 0xFF68..=0xFF6C | 0xFF70 | 0xFF72..=0xFF77 => self.read_cgb_reg(address, op_src),
+
             // Interrupt enable
             0xFFFF => {
                 print!("reading IE register, value is {}\n", self.hw_reg.ie);
@@ -307,10 +686,10 @@ impl Mbc {
                 //self.ram.write(address, byte);
                 self.rom_only_write(address, byte);
             }
-            RomType::MBC1 => {
+            RomType::MBC1 | RomType::MBC1_RAM | RomType::MBC1_RAM_BATT => {
                 self.mbc1_write(address, byte);
             }
-            RomType::MBC3 => {
+            RomType::MBC3 | RomType::MBC3_RAM | RomType::MBC3_RAM_BATT | RomType::MBC3_RAM_BATT_RTC | RomType::MBC3_BATT_RTC => {
                 self.mbc3_write(address, byte);
             },
             _ => {
@@ -332,6 +711,11 @@ impl Mbc {
             return;
         }
 
+        // writes the dma is in the way of are lost
+        if op_src == OpSource::CPU && (self.is_dma_bus_conflict(address) || (self.dma_active && (0xFE00..=0xFE9F).contains(&address))) {
+            return;
+        }
+
         // handle special writes: BIOS, hw reg, then ROM
         match address {
             //BIOS
@@ -351,11 +735,14 @@ impl Mbc {
             // writing 0x20 means select buttons
             // writing 0x10 means select direction keys
             // writing 0x30 means nither, lower nibble should read 0xF
+            // writing 0x00 selects both
             0xFF00 => {
-                println!("writing to joyp hwreg byte {:#x}", byte);
-                let bit_4_and_5 = byte & 0b0011_0000;
-                self.hw_reg.joyp |= bit_4_and_5;
-                self.is_joypad_pending_update_from_reg = true;
+                if let Some(sgb) = self.sgb.as_mut() {
+                    sgb.write_joyp(byte);
+                }
+                if self.joypad.write(byte) {
+                    self.hw_reg.set_if_joypad_bit4();
+                }
             },
 
             // 0xFF01 => self.hw_reg.sb = byte,
@@ -366,6 +753,11 @@ impl Mbc {
             0xFF02 =>  {
                 if byte == 0x81 {
                     print!("{}", self.hw_reg.sb as char);
+                    self.serial_out.push(self.hw_reg.sb);
+                    if self.serial_out.len() > SERIAL_OUT_LIMIT {
+                        // drop the older half so this doesn't happen on every byte
+                        self.serial_out.drain(..SERIAL_OUT_LIMIT / 2);
+                    }
                     self.hw_reg.sc = 0x00;
                 } else {
                     self.hw_reg.sc = byte;
@@ -383,8 +775,10 @@ impl Mbc {
             // STOP inst also resets this and begins again after STOP ends
             0xFF04 =>  {
                 // writing to DIV resets it
-                self.reset_cpu_counter = true;
+                let last_counter = self.div_counter;
+                self.div_counter = 0;
                 self.hw_reg.div = 0;
+                self.tick_tima_reg(last_counter, 0);
             },
 
             // increments at rate selected by TAC
@@ -430,16 +824,9 @@ impl Mbc {
             0xFF44 => self.hw_reg.ly = 0, // writing to LY resets it
             0xFF45 => self.hw_reg.lyc = byte,
             0xFF46 => {
-
-                print!("writing to 0xFF46, DMA hw register \n");
-                self.dma_active = true;
-                self.dma_cycles_remaining = 160;
-                let read_add_base = (byte as u16) << 8;
-                let write_add_base = 0xFE00;
-                for i    in 0..160u16 {
-                    let val = self.read(read_add_base + i, op_src);
-                    self.write(write_add_base + i, val, op_src);
-                }
+                // the transfer runs in Mbc::tick_dma, one byte per mcycle
+                self.dma_pending_source = (byte as u16) << 8;
+                self.dma_start_delay = 2;
                 self.hw_reg.dma = byte;
             },
             0xFF47 => self.hw_reg.bgp = byte,
@@ -457,11 +844,12 @@ impl Mbc {
 
             // Boot ROM control
             0xFF50 => {
-                if byte == 1 {
+                // the dmg boot rom writes 1, the cgb one 0x11
+                if byte & 0b0000_0001 != 0 {
                     print!("writing {} to 0xFF50, Boot ROM control hw register \n", byte);
                     self.hw_reg.boot_rom_control = byte;
                 } else {
-                    print!("ignoring write to 0xFF50 Boot ROM control since it wasn't 1 \n");
+                    print!("ignoring write to 0xFF50 Boot ROM control since bit 0 wasn't set \n");
 
                 }
 
@@ -498,6 +886,9 @@ impl Mbc {
                 self.hw_reg.wave_pattern[index] = byte;
             }
 
+            // CGB
+            0xFF4C | 0xFF4D | 0xFF4F | 0xFF51..=0xFF56 | 0xFF68..=0xFF6C | 0xFF70 | 0xFF72..=0xFF77 => self.write_cgb_reg(address, byte, op_src),
+
             // Interrupt enable
             // 0xFFFF => self.hw_reg.ie = byte,
             
@@ -525,16 +916,14 @@ impl Mbc {
             self.ram.read(address);
         } else if (0x8000..=0x9FFF).contains(&address) {
             // read V RAM
-            let ram_offset: u16 = 0x8000;
-            return self.vram.read(address - ram_offset);
+            return self.vram.read(self.get_vram_index(address));
         } else if (0xA000..=0xBFFF).contains(&address) {
             // read X RAM
             let ram_offset: u16 = 0xA000;
             return self.xram.read(address - ram_offset);
         } else if (0xC000..=0xDFFF).contains(&address) {
             // read W RAM
-            let ram_offset: u16 = 0xC000;
-            return self.wram.read(address - ram_offset);
+            return self.wram.read(self.get_wram_index(address));
         } else if (0xFE00..=0xFE9F).contains(&address) {
             // read OAM
             let oam_offset: u16 = 0xFE00;
@@ -561,8 +950,7 @@ impl Mbc {
             //panic!("write to rom in rom_only_write address {}, byte {}", address, byte);
         } else if (0x8000..=0x9FFF).contains(&address) {
             // read VRAM
-            let ram_offset: u16 = 0x8000;
-            self.vram.write(address - ram_offset, byte);
+            self.vram.write(self.get_vram_index(address), byte);
             return;
         } else if (0xA000..=0xBFFF).contains(&address) {
             // read XRAM
@@ -571,8 +959,7 @@ impl Mbc {
             return;
         } else if (0xC000..=0xDFFF).contains(&address) {
             // read WRAM
-            let ram_offset: u16 = 0xC000;
-            self.wram.write(address - ram_offset, byte);
+            self.wram.write(self.get_wram_index(address), byte);
             return;
         } else if (0xFE00..=0xFE9F).contains(&address) {
             // read OAM
@@ -622,11 +1009,10 @@ impl Mbc {
             }
         } else if (0x8000..=0x9FFF).contains(&address) {
             // read VRAM
-            let vram_base_size: u16 = 0x8000;
             // if address == 0x9800 {
             //     print!("reading address 0x9800\n ");
             // }
-            return self.vram.read(address - vram_base_size);
+            return self.vram.read(self.get_vram_index(address));
         } else if (0xA000..=0xBFFF).contains(&address) {
             //read XRAM
             let ram_bank_size: u16 = 0x2000;
@@ -642,8 +1028,7 @@ impl Mbc {
             }
         } else if (0xC000..=0xDFFF).contains(&address) {
             // read WRAM
-            let ram_base_size: u16 = 0xC000;
-            return self.wram.read(address - ram_base_size);
+            return self.wram.read(self.get_wram_index(address));
         } else if (0xFE00..=0xFE9F).contains(&address) {
             // read OAM
             let oam_offset: u16 = 0xFE00;
@@ -713,11 +1098,10 @@ impl Mbc {
             return;
         } else if (0x8000..=0x9FFF).contains(&address) {
             // write to VRAM
-            let vram_base_size: u16 = 0x8000;
             if address == 0x9800 {
                 print!("writing {:#X} to address 0x9800\n", byte);
             }
-            self.vram.write(address - vram_base_size, byte);
+            self.vram.write(self.get_vram_index(address), byte);
             return;
         } else if (0xA000..=0xBFFF).contains(&address) {
             //write to XRAM
@@ -736,8 +1120,7 @@ impl Mbc {
             }
         } else if (0xC000..=0xDFFF).contains(&address) {
             // read WRAM
-            let ram_base_size: u16 = 0xC000;
-            self.wram.write(address - ram_base_size, byte);
+            self.wram.write(self.get_wram_index(address), byte);
             return;
         } else if (0xFE00..=0xFE9F).contains(&address) {
             // read OAM
@@ -762,57 +1145,75 @@ impl Mbc {
     }
 
     pub fn mbc3_read(&self, address: u16) -> u8 {
-        // read from rom bank 0
-        if  (0x0000..=0x3FFF).contains(&address) {
-            self.rom.as_ref().unwrap().read(address as u32);
-        } else if (0x4000..=0x7FFF).contains(&address) { // read from rom bank 1 to X
-            let base: u32 = (self.rom_bank as u32) * 0x4000;
-            let offset: u32 = (address as u32) - 0x4000;
-            let calculated_address:u32 = base + offset;
-            return self.rom.as_ref().unwrap().read(calculated_address);
+        let rom = self.rom.as_ref().unwrap();
+        if (0x0000..=0x3FFF).contains(&address) {
+            // read from rom bank 0
+            return rom.read(address as u32);
+        } else if (0x4000..=0x7FFF).contains(&address) {
+            // read from rom bank 1 to 7F, wrapped to the rom's size
+            let calculated_address = (self.rom_bank as u32) * 0x4000 + (address as u32 - 0x4000);
+            return rom.read(calculated_address % rom.data.len() as u32);
+        } else if (0x8000..=0x9FFF).contains(&address) {
+            return self.vram.read(self.get_vram_index(address));
+        } else if (0xA000..=0xBFFF).contains(&address) {
+            // ram bank 0 to 3, or 8 to C for the clock registers
+            if !self.wr_ram_bank {
+                return 0xFF;
+            }
+            if self.ram_bank >= 0x08 {
+                return self.rtc_registers[(self.ram_bank - 0x08) as usize];
+            }
+            let calculated_address = (self.ram_bank as u32) * 0x2000 + (address as u32 - 0xA000);
+            return self.rom_ram.read(calculated_address);
+        } else if (0xC000..=0xDFFF).contains(&address) {
+            return self.wram.read(self.get_wram_index(address));
+        } else if (0xFE00..=0xFE9F).contains(&address) {
+            return self.oam.read(address - 0xFE00);
+        } else if (0xFF00..=0xFF7F).contains(&address) {
+            return self.io.read(address - 0xFF00);
+        } else if (0xFF80..=0xFFFE).contains(&address) {
+            return self.hram.read(address - 0xFF80);
         }
-
-        self.rom.as_ref().unwrap().read(address as u32)
+        self.ram.read(address)
     }
 
-    pub fn mbc3_write(&mut self, address: u16, byte: u8) -> u8 {
-        // enable or diable ram write
+    pub fn mbc3_write(&mut self, address: u16, byte: u8) {
         if (0x0000..=0x1FFF).contains(&address) {
-            if byte & 0xA0 == 0xA0 {
-                self.wr_ram_bank = true;
-            }
-            else {
-                self.wr_ram_bank = false;
-            }
-        }
-        // switch rom banks
-        else if (0x2000..=0x3FFF).contains(&address) {
-            // sets the rom bank number for 0x4000-7FFF
-            if byte == 0 {
-                // 0 must always be rom bank 1
-                self.rom_bank = 1;
+            // enable or disable the ram and clock
+            self.wr_ram_bank = (byte & 0x0F) == 0x0A;
+        } else if (0x2000..=0x3FFF).contains(&address) {
+            // 7 bit rom bank for 4000-7FFF, 0 still means 1
+            let bank = byte & 0b0111_1111;
+            self.rom_bank = if bank == 0 { 1 } else { bank };
+        } else if (0x4000..=0x5FFF).contains(&address) {
+            // 0-3 picks a ram bank, 8-C puts a clock register at A000-BFFF instead
+            if byte <= 0x03 || (0x08..=0x0C).contains(&byte) {
+                self.ram_bank = byte;
             }
-            else {
-                // this is a 5 bit value so discard any higher bits
-                self.rom_bank = byte & 0b_0001_1111;
+        } else if (0x6000..=0x7FFF).contains(&address) {
+            // writing 0 then 1 latches the clock, it doesn't count so there's nothing to latch
+        } else if (0x8000..=0x9FFF).contains(&address) {
+            self.vram.write(self.get_vram_index(address), byte);
+        } else if (0xA000..=0xBFFF).contains(&address) {
+            if !self.wr_ram_bank {
+                return;
             }
-        }
-        // for 1MB roms - bit 5-6 of ROM bank num
-        // for 32KB ram - RAM bank num
-        // else ignore
-
-        else if  (0x4000..=0x5FFF).contains(&address) {
-            if self.rom.as_ref().unwrap().rom_size == RomSize::MB_1 {
-
+            if self.ram_bank >= 0x08 {
+                self.rtc_registers[(self.ram_bank - 0x08) as usize] = byte;
+                return;
             }
-        }
-        // write to ram bank X
-        else if (0xA000..=0xBFFF).contains(&address) {
-            let base = (self.ram_bank as u32) * 0x2000;
-            let offset = (address as u32) - 0xA000;
-            let calculated_address = base + offset;
+            let calculated_address = (self.ram_bank as u32) * 0x2000 + (address as u32 - 0xA000);
             self.rom_ram.write(calculated_address, byte);
+        } else if (0xC000..=0xDFFF).contains(&address) {
+            self.wram.write(self.get_wram_index(address), byte);
+        } else if (0xFE00..=0xFE9F).contains(&address) {
+            self.oam.write(address - 0xFE00, byte);
+        } else if (0xFF00..=0xFF7F).contains(&address) {
+            self.io.write(address - 0xFF00, byte);
+        } else if (0xFF80..=0xFFFE).contains(&address) {
+            self.hram.write(address - 0xFF80, byte);
+        } else {
+            self.ram.write(address, byte);
         }
-        panic!("attempted an unhandled write inside mbc3_write address {} byte {:X}", address, byte);
     }
 }
\ No newline at end of file
@@ -0,0 +1,165 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::gb::headless::{Headless, is_at_ld_b_b, panic_message};
+use crate::gb::mbc::Mbc;
+use crate::gb::rom::Rom;
+
+// runs the mooneye test roms in test_roms/ without a window
+// every test ends on LD B,B, a pass leaves the fibonacci numbers 3/5/8/13/21/34 in B C D E H L
+// and a fail leaves 0x42 in all of them
+// https://github.com/Gekkio/mooneye-test-suite
+
+pub const MOONEYE_DIR: &str = "test_roms";
+pub const KNOWN_FAILURES_FILE: &str = "tests/mooneye_known_failures.txt";
+// 10 emulated seconds, the tests themselves finish in well under one after the boot rom
+pub const DEFAULT_TIMEOUT_MCYCLES: u64 = 10 * 1_048_576;
+
+const PASS_REGISTERS: [u8; 6] = [3, 5, 8, 13, 21, 34];
+const FAIL_REGISTERS: [u8; 6] = [0x42; 6];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MooneyeResult {
+    Pass,
+    Fail([u8; 6]), // B C D E H L, 0x42s unless the test wrote its own values
+    Timeout,
+    Crash(String), // the emu panicked
+    Unsupported(String), // the cart's mapper isn't emulated yet, the rom never ran
+    Skip, // made for a model we don't emulate
+}
+
+impl MooneyeResult {
+    pub fn is_pass(&self) -> bool {
+        *self == MooneyeResult::Pass
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            MooneyeResult::Pass => String::from("pass"),
+            MooneyeResult::Fail(regs) if *regs == FAIL_REGISTERS => String::from("fail"),
+            MooneyeResult::Fail(regs) => format!(
+                "fail B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X}",
+                regs[0], regs[1], regs[2], regs[3], regs[4], regs[5]
+            ),
+            MooneyeResult::Timeout => String::from("timeout"),
+            MooneyeResult::Crash(msg) => format!("crash: {}", msg),
+            MooneyeResult::Unsupported(msg) => format!("unsupported: {}", msg),
+            MooneyeResult::Skip => String::from("skip"),
+        }
+    }
+}
+
+// the part after the last - is the models the test is for, no suffix means every model
+// G is dmg0/dmgABC/mgb and S is sgb/sgb2 in the short form, otherwise they're spelled out
+pub fn is_for_dmg(rom_file: &str) -> bool {
+    let name = Path::new(rom_file).file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    match name.rsplit_once('-') {
+        None => true,
+        Some((_, models)) if models.chars().all(|c| c.is_ascii_uppercase()) => models.contains('G'),
+        Some((_, models)) => models.contains("dmgABC"),
+    }
+}
+
+pub fn run_mooneye_rom(rom_file: &str, timeout_mcycles: u64) -> MooneyeResult {
+    if !is_for_dmg(rom_file) {
+        return MooneyeResult::Skip;
+    }
+    // checked up front so a mapper we don't have is reported as such instead of a crash
+    if let Ok(rom) = Rom::load(rom_file) {
+        if !Mbc::is_mapper_supported(rom.get_rom_type()) {
+            return MooneyeResult::Unsupported(format!("{:?} carts aren't supported yet", rom.get_rom_type()));
+        }
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut headless = Headless::new(rom_file);
+        let is_done = headless.run_until(timeout_mcycles, is_at_ld_b_b);
+        if !is_done {
+            return MooneyeResult::Timeout;
+        }
+        let regs = &headless.emu.cpu.registers;
+        let values = [regs.get_b(), regs.get_c(), regs.get_d(), regs.get_e(), regs.get_h(), regs.get_l()];
+        if values == PASS_REGISTERS {
+            MooneyeResult::Pass
+        } else {
+            MooneyeResult::Fail(values)
+        }
+    }));
+    result.unwrap_or_else(|e| MooneyeResult::Crash(panic_message(e)))
+}
+
+// every .gb under dir, sorted so the table comes out the same each run
+pub fn find_roms(dir: &Path) -> Vec<PathBuf> {
+    let mut roms = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else { return roms; };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            roms.extend(find_roms(&path));
+        } else if path.extension().is_some_and(|ext| ext == "gb") {
+            roms.push(path);
+        }
+    }
+    roms.sort();
+    roms
+}
+
+// roms relative to test_roms/, one per line, # starts a comment
+pub fn load_known_failures(file: &str) -> Vec<String> {
+    let text = fs::read_to_string(file).unwrap_or_default();
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// gbemu mooneye [dir or rom...]
+pub fn run_mooneye_command(args: &[String]) {
+    let paths: Vec<PathBuf> = if args.is_empty() {
+        ["acceptance", "emulator-only", "misc", "madness"].iter().map(|dir| Path::new(MOONEYE_DIR).join(dir)).collect()
+    } else {
+        args.iter().map(PathBuf::from).collect()
+    };
+    let mut roms = Vec::new();
+    for path in &paths {
+        if path.is_dir() {
+            roms.extend(find_roms(path));
+        } else {
+            roms.push(path.clone());
+        }
+    }
+    let known_failures = load_known_failures(KNOWN_FAILURES_FILE);
+
+    // the emu is chatty, so the table is only printed once everything has run
+    let mut results = Vec::new();
+    for rom in &roms {
+        let rom = rom.to_string_lossy().to_string();
+        let result = run_mooneye_rom(&rom, DEFAULT_TIMEOUT_MCYCLES);
+        results.push((rom, result));
+    }
+
+    let width = results.iter().map(|(rom, _)| rom.len()).max().unwrap_or(0);
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    println!();
+    println!("{:<width$}  result", "rom", width = width);
+    println!("{}", "-".repeat(width + 8));
+    for (rom, result) in &results {
+        let name = rom.trim_start_matches(MOONEYE_DIR).trim_start_matches('/');
+        let is_known = known_failures.iter().any(|known| known == name);
+        let note = match result {
+            MooneyeResult::Pass if is_known => "  (known failure passed, remove it from the list)",
+            MooneyeResult::Pass | MooneyeResult::Skip => "",
+            _ if is_known => "  (known)",
+            _ => "",
+        };
+        println!("{:<width$}  {}{}", rom, result.describe(), note, width = width);
+        match result {
+            MooneyeResult::Pass => passed += 1,
+            MooneyeResult::Skip => skipped += 1,
+            _ => failed += 1,
+        }
+    }
+    println!("{}", "-".repeat(width + 8));
+    println!("{} passed, {} failed, {} skipped", passed, failed, skipped);
+}
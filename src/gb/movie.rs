@@ -0,0 +1,171 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+use crate::gb::joypad::Button;
+
+// input movies, the buttons held on every frame from a known starting state
+// playing one back on the same rom gives the same emulation down to the cycle
+//
+// the file is plain text so it diffs and can be edited by hand:
+//   gbemu movie 1
+//   rom mm2.gb
+//   crc32 1A2B3C4D
+//   start power-on
+//   frames
+//   ........
+//   ...R...A
+// one line per frame in UDLRsSBA order (s is select, S is start), '.' for not held
+// line n is what gets held after frame n ends, frame 0 starts with nothing held or whatever the save state had
+// a movie recorded after the emu has already run starts from a save state instead, `start state <hex>`
+// with the whole state on that line, see savestate.rs
+
+pub const MOVIE_HEADER: &str = "gbemu movie 1";
+const FRAMES_LINE: &str = "frames";
+const BUTTON_ORDER: [(Button, char); 8] = [
+    (Button::Up, 'U'),
+    (Button::Down, 'D'),
+    (Button::Left, 'L'),
+    (Button::Right, 'R'),
+    (Button::Select, 's'),
+    (Button::Start, 'S'),
+    (Button::B, 'B'),
+    (Button::A, 'A'),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MovieStart {
+    PowerOn,
+    State(Vec<u8>), // Emu::save_state
+}
+
+impl MovieStart {
+    pub fn format(&self) -> String {
+        match self {
+            MovieStart::PowerOn => String::from("power-on"),
+            MovieStart::State(data) => format!("state {}", data.iter().map(|byte| format!("{:02X}", byte)).collect::<String>()),
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text.split_once(' ').unwrap_or((text, "")) {
+            ("power-on", "") => Some(MovieStart::PowerOn),
+            ("state", hex) if !hex.is_empty() && hex.len() % 2 == 0 => {
+                let data = (0..hex.len()).step_by(2)
+                    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<Vec<u8>>>()?;
+                Some(MovieStart::State(data))
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie {
+    pub rom: String, // just for people reading the file, the crc is what gets checked
+    pub rom_crc32: u32,
+    pub start: MovieStart,
+    pub frames: Vec<u8>, // Button bits
+}
+
+impl Movie {
+    pub fn load(file: &str) -> io::Result<Self> {
+        Movie::parse(&fs::read_to_string(file)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file, e)))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().map(|(idx, line)| (idx + 1, line.trim()));
+        match lines.next() {
+            Some((_, MOVIE_HEADER)) => {},
+            _ => return Err(format!("not a movie, the first line should be \"{}\"", MOVIE_HEADER)),
+        }
+
+        let mut rom = String::new();
+        let mut rom_crc32 = None;
+        let mut start = None;
+        for (line_num, line) in lines.by_ref() {
+            if line == FRAMES_LINE {
+                break;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "rom" => rom = String::from(value),
+                "crc32" => rom_crc32 = Some(u32::from_str_radix(value, 16).map_err(|_| format!("line {}: bad crc32 {}", line_num, value))?),
+                "start" => start = Some(MovieStart::parse(value).ok_or_else(|| format!("line {}: bad start {:.40}", line_num, value))?),
+                "" => {},
+                _ => println!("ignoring unknown movie line {}: {}", line_num, line),
+            }
+        }
+
+        let mut frames = Vec::new();
+        for (line_num, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            frames.push(parse_frame(line).ok_or_else(|| format!("line {}: bad frame {}", line_num, line))?);
+        }
+
+        Ok(Movie {
+            rom,
+            rom_crc32: rom_crc32.ok_or("missing crc32 line")?,
+            start: start.ok_or("missing start line")?,
+            frames,
+        })
+    }
+}
+
+// writes the header up front and every frame as it's latched, so a killed process still leaves a playable movie
+// closing the window finishes it through Emu::shut_down
+pub struct MovieWriter {
+    writer: BufWriter<File>,
+    pub frames: u64,
+}
+
+impl MovieWriter {
+    pub fn new(file: &str, rom: &str, rom_crc32: u32, start: MovieStart) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        writeln!(writer, "{}", MOVIE_HEADER)?;
+        writeln!(writer, "rom {}", rom)?;
+        writeln!(writer, "crc32 {:08X}", rom_crc32)?;
+        writeln!(writer, "start {}", start.format())?;
+        writeln!(writer, "{}", FRAMES_LINE)?;
+        writer.flush()?;
+        Ok(MovieWriter {
+            writer,
+            frames: 0,
+        })
+    }
+
+    pub fn write_frame(&mut self, buttons: u8) -> io::Result<()> {
+        writeln!(self.writer, "{}", format_frame(buttons))?;
+        self.writer.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.writer.flush()?;
+        Ok(self.frames)
+    }
+}
+
+pub fn format_frame(buttons: u8) -> String {
+    BUTTON_ORDER.iter()
+        .map(|&(button, c)| if buttons & button.get_bit() != 0 { c } else { '.' })
+        .collect()
+}
+
+pub fn parse_frame(line: &str) -> Option<u8> {
+    if line.chars().count() != BUTTON_ORDER.len() {
+        return None;
+    }
+    let mut buttons = 0;
+    for (c, &(button, expected)) in line.chars().zip(BUTTON_ORDER.iter()) {
+        if c == expected {
+            buttons |= button.get_bit();
+        } else if c != '.' {
+            return None;
+        }
+    }
+    Some(buttons)
+}
@@ -0,0 +1,87 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::gb::constants::MCYCLES_PER_FRAME;
+
+// keeps emulated frames in step with the host clock
+// every 70224 tcycles (17556 mcycles) the emu thread sleeps until that frame is due, ~59.73 a second
+// frames are due relative to when pacing (re)started, so sleep overshoot doesn't add up over time
+// there's no apu yet, once there is this is where pacing to the audio buffer would go
+
+const FRAME_SECS: f64 = 70_224.0 / 4_194_304.0;
+// further behind than this and it stops trying to catch up, after a breakpoint or on a slow host
+const MAX_LAG: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Speed {
+    Times(f64), // 1.0 is real time, 2.0 double, 0.5 slow motion
+    Unthrottled,
+}
+
+impl Speed {
+    // 1, 2x, 0.25, unthrottled
+    pub fn parse(text: &str) -> Option<Self> {
+        if text == "unthrottled" {
+            return Some(Speed::Unthrottled);
+        }
+        match text.trim_end_matches('x').parse::<f64>() {
+            Ok(times) if times > 0.0 && times.is_finite() => Some(Speed::Times(times)),
+            _ => None,
+        }
+    }
+}
+
+pub struct FramePacer {
+    pub speed: Speed,
+    start: Instant,
+    frames: u64, // since start
+    mcycles: u64, // into the current frame
+}
+
+impl FramePacer {
+    pub fn new(speed: Speed) -> Self {
+        FramePacer {
+            speed,
+            start: Instant::now(),
+            frames: 0,
+            mcycles: 0,
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: Speed) {
+        if speed != self.speed {
+            println!("speed is now {:?}", speed);
+        }
+        self.speed = speed;
+        self.restart();
+    }
+
+    // after a pause, or anything else that shouldn't be caught up on
+    pub fn restart(&mut self) {
+        self.start = Instant::now();
+        self.frames = 0;
+    }
+
+    // called with the mcycles of every step, sleeps when a frame's worth has run
+    pub fn add_mcycles(&mut self, mcycles: u64) {
+        self.mcycles += mcycles;
+        if self.mcycles < MCYCLES_PER_FRAME {
+            return;
+        }
+        self.frames += self.mcycles / MCYCLES_PER_FRAME;
+        self.mcycles %= MCYCLES_PER_FRAME;
+        self.sync();
+    }
+
+    fn sync(&mut self) {
+        let Speed::Times(times) = self.speed else { return; };
+        let due = self.start + Duration::from_secs_f64(self.frames as f64 * FRAME_SECS / times);
+        let now = Instant::now();
+        if due > now {
+            thread::sleep(due - now);
+        } else if now - due > MAX_LAG {
+            println!("running {} ms behind at {:?}, not catching up", (now - due).as_millis(), self.speed);
+            self.restart();
+        }
+    }
+}
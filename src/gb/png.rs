@@ -0,0 +1,167 @@
+use std::fs;
+use std::io;
+
+// rgba frames to and from png files, encoded here instead of with a crate
+// the pixels go out as stored (uncompressed) deflate blocks, so a file is about the size of its raw rgba
+// load_png reads what save_png writes and nothing fancier: 8 bit rgba, unfiltered rows, stored blocks
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+// 8 bits per channel, rgba, deflate, the basic filters, no interlacing
+const RGBA_HEADER: [u8; 5] = [8, 6, 0, 0, 0];
+const MAX_STORED_BLOCK: usize = 65_535;
+
+pub fn save_png(file: &str, width: u32, height: u32, rgba: &[u8]) -> io::Result<()> {
+    fs::write(file, encode_png(width, height, rgba))
+}
+
+pub fn load_png(file: &str) -> io::Result<(u32, u32, Vec<u8>)> {
+    decode_png(&fs::read(file)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file, e)))
+}
+
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&RGBA_HEADER);
+
+    // every row starts with filter type 0, the bytes as they are
+    let row_len = width as usize * 4;
+    let mut rows = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in rgba.chunks(row_len.max(1)).take(height as usize) {
+        rows.push(0);
+        rows.extend_from_slice(row);
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&rows));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+pub fn decode_png(data: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(String::from("not a png"));
+    }
+    let mut size = None;
+    let mut zlib = Vec::new();
+    let mut pos = SIGNATURE.len();
+    loop {
+        let len = read_u32_be(data, pos).ok_or("missing IEND chunk")? as usize;
+        let kind = data.get(pos + 4..pos + 8).ok_or("missing IEND chunk")?;
+        let body = data.get(pos + 8..pos + 8 + len).ok_or("chunk runs past the end of the file")?;
+        let crc = read_u32_be(data, pos + 8 + len).ok_or("chunk runs past the end of the file")?;
+        if crc != crc32(&data[pos + 4..pos + 8 + len]) {
+            return Err(format!("bad crc in {} chunk", String::from_utf8_lossy(kind)));
+        }
+        match kind {
+            b"IHDR" => {
+                if len != 13 || body[8..] != RGBA_HEADER {
+                    return Err(String::from("only 8 bit rgba pngs without interlacing can be read"));
+                }
+                size = Some((read_u32_be(body, 0).unwrap(), read_u32_be(body, 4).unwrap()));
+            },
+            b"IDAT" => zlib.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {},
+        }
+        pos += 12 + len;
+    }
+
+    let (width, height) = size.ok_or("missing IHDR chunk")?;
+    let rows = inflate_stored(&zlib)?;
+    let row_len = width as usize * 4;
+    if rows.len() != (row_len + 1) * height as usize {
+        return Err(format!("image data is {} bytes, {}x{} needs {}", rows.len(), width, height, (row_len + 1) * height as usize));
+    }
+    let mut rgba = Vec::with_capacity(row_len * height as usize);
+    for row in rows.chunks(row_len + 1) {
+        if row[0] != 0 {
+            return Err(format!("row filter {} isn't supported, only unfiltered rows are", row[0]));
+        }
+        rgba.extend_from_slice(&row[1..]);
+    }
+    Ok((width, height, rgba))
+}
+
+// the usual zlib/png crc
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+fn read_u32_be(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().unwrap()))
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// a zlib stream made of stored deflate blocks, each one a 5 byte header and up to 65535 bytes as they are
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut rest = data;
+    loop {
+        let len = rest.len().min(MAX_STORED_BLOCK);
+        let is_final = len == rest.len();
+        zlib.push(is_final as u8);
+        zlib.extend_from_slice(&(len as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(len as u16)).to_le_bytes());
+        zlib.extend_from_slice(&rest[..len]);
+        rest = &rest[len..];
+        if is_final {
+            break;
+        }
+    }
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+fn inflate_stored(zlib: &[u8]) -> Result<Vec<u8>, String> {
+    if zlib.len() < 2 || zlib[0] & 0x0F != 8 {
+        return Err(String::from("image data isn't a zlib stream"));
+    }
+    let mut data = Vec::new();
+    let mut pos = 2;
+    loop {
+        let header = *zlib.get(pos).ok_or("image data ends early")?;
+        if header & 0b110 != 0 {
+            return Err(String::from("only uncompressed (stored) image data can be read"));
+        }
+        let len = u16::from_le_bytes(zlib.get(pos + 1..pos + 3).ok_or("image data ends early")?.try_into().unwrap());
+        let nlen = u16::from_le_bytes(zlib.get(pos + 3..pos + 5).ok_or("image data ends early")?.try_into().unwrap());
+        if len != !nlen {
+            return Err(String::from("bad stored block length"));
+        }
+        data.extend_from_slice(zlib.get(pos + 5..pos + 5 + len as usize).ok_or("image data ends early")?);
+        pos += 5 + len as usize;
+        if header & 1 != 0 {
+            break;
+        }
+    }
+    if read_u32_be(zlib, pos) != Some(adler32(&data)) {
+        return Err(String::from("bad adler32 in image data"));
+    }
+    Ok(data)
+}
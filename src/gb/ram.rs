@@ -6,6 +6,8 @@ pub struct Ram {
     pub memory: Vec<u8>,
 }
 
+crate::impl_state!(Ram { memory });
+
 
 impl Ram {
     pub fn new(init_val: u8) -> Self {
@@ -34,6 +36,8 @@ pub struct RomRam {
     pub memory: [u8; 131071],
 }
 
+crate::impl_state!(RomRam { memory });
+
 
 impl RomRam {
     pub fn new() -> Self {
@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// records the game screen as an uncompressed y4m video, one video frame per emulated frame
+// 4:4:4 so nothing is lost to chroma subsampling, ffmpeg and mpv play it as is
+// the frame rate is the real one, 4194304 / 70224 = ~59.73 fps
+
+pub const FRAME_RATE: &str = "4194304:70224";
+
+pub struct VideoRecorder {
+    writer: BufWriter<File>,
+    width: usize,
+    height: usize,
+    pub frames: u64,
+}
+
+impl VideoRecorder {
+    pub fn new(file: &str, width: usize, height: usize) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        writeln!(writer, "YUV4MPEG2 W{} H{} F{} Ip A1:1 C444 XCOLORRANGE=FULL", width, height, FRAME_RATE)?;
+        Ok(VideoRecorder {
+            writer,
+            width,
+            height,
+            frames: 0,
+        })
+    }
+
+    pub fn write_frame(&mut self, rgba: &[u8]) -> io::Result<()> {
+        let pixels = self.width * self.height;
+        let mut planes = vec![0u8; pixels * 3];
+        for (idx, px) in rgba.chunks(4).take(pixels).enumerate() {
+            let (y, u, v) = rgb_to_yuv(px[0], px[1], px[2]);
+            planes[idx] = y;
+            planes[pixels + idx] = u;
+            planes[pixels * 2 + idx] = v;
+        }
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&planes)?;
+        // shut_down finishes the file when the window closes, flushing every frame keeps it playable if the process is killed instead
+        self.writer.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    // a white screen, what the lcd shows while it's off
+    pub fn write_blank_frame(&mut self) -> io::Result<()> {
+        let white = vec![0xFF; self.width * self.height * 4];
+        self.write_frame(&white)
+    }
+
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.writer.flush()?;
+        Ok(self.frames)
+    }
+}
+
+// full range bt.601, greys map to u = v = 128 exactly
+fn rgb_to_yuv(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let u = 128.0 - 0.168_736 * r - 0.331_264 * g + 0.5 * b;
+    let v = 128.0 + 0.5 * r - 0.418_688 * g - 0.081_312 * b;
+    (y.round().clamp(0.0, 255.0) as u8, u.round().clamp(0.0, 255.0) as u8, v.round().clamp(0.0, 255.0) as u8)
+}
@@ -0,0 +1,129 @@
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use crate::gb::constants::MCYCLES_PER_FRAME;
+use crate::gb::headless::*;
+use crate::gb::png::{load_png, save_png};
+
+// runs a rom headless and compares the screen against a stored png
+// pixels are compared by shade rather than exact rgb, so references taken with other
+// palettes (dmg-acid2's own uses AA/55 greys) still match
+// set GBEMU_UPDATE_REFERENCE=1 to write the current output as the new reference
+
+pub const REFERENCE_DIR: &str = "tests/reference";
+pub const OUTPUT_DIR: &str = "target/reftest"; // actual and diff images of failed runs go here
+// frames a ld b,b run gets before giving up, about 30 seconds
+pub const DEFAULT_MAX_FRAMES: u64 = 1800;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopAt {
+    Frames(u64), // after the boot rom
+    LdBB, // the rom says it's done with LD B,B
+    Pc(u16),
+}
+
+pub struct RefTest {
+    pub name: String, // reference is tests/reference/<name>.png
+    pub rom: String,
+    pub stop_at: StopAt,
+}
+
+impl RefTest {
+    pub fn new(name: &str, rom: &str, stop_at: StopAt) -> Self {
+        RefTest {
+            name: String::from(name),
+            rom: String::from(rom),
+            stop_at,
+        }
+    }
+
+    pub fn reference_file(&self) -> String {
+        format!("{}/{}.png", REFERENCE_DIR, self.name)
+    }
+
+    // the frame the rom ends up on
+    pub fn capture(&self) -> Result<Vec<u8>, String> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut headless = Headless::new(&self.rom);
+            let max_mcycles = DEFAULT_MAX_FRAMES * MCYCLES_PER_FRAME;
+            // frames are counted from when the boot rom hands over, its length depends on ppu timing
+            if !headless.run_until(max_mcycles, |emu| emu.mbc.hw_reg.boot_rom_control != 0) {
+                return Err(format!("{} never finished the boot rom", self.rom));
+            }
+            let is_stopped = match self.stop_at {
+                StopAt::Frames(frames) => headless.run_frames(frames),
+                StopAt::LdBB => headless.run_until(max_mcycles, is_at_ld_b_b),
+                StopAt::Pc(pc) => headless.run_until(max_mcycles, |emu| emu.cpu.registers.get_pc() == pc),
+            };
+            if !is_stopped {
+                return Err(format!("{} never got to {:?}", self.rom, self.stop_at));
+            }
+            // a breakpoint can hit halfway down the screen, let the frame finish
+            if !matches!(self.stop_at, StopAt::Frames(_)) {
+                headless.run_frames(1);
+            }
+            Ok(headless.copy_game_buffer())
+        }));
+        result.unwrap_or_else(|e| Err(format!("crash: {}", panic_message(e))))
+    }
+
+    pub fn run(&self) -> Result<(), String> {
+        let actual = self.capture()?;
+        let reference_file = self.reference_file();
+        if env::var("GBEMU_UPDATE_REFERENCE").is_ok_and(|v| v == "1") {
+            let _ = fs::create_dir_all(REFERENCE_DIR);
+            save_png(&reference_file, GAME_WIDTH, GAME_HEIGHT, &actual).map_err(|e| e.to_string())?;
+            println!("wrote {}", reference_file);
+            return Ok(());
+        }
+
+        let (width, height, expected) = load_png(&reference_file)
+            .map_err(|e| format!("unable to load {}: {}", reference_file, e))?;
+        if (width, height) != (GAME_WIDTH, GAME_HEIGHT) {
+            return Err(format!("{} is {}x{}, expected {}x{}", reference_file, width, height, GAME_WIDTH, GAME_HEIGHT));
+        }
+
+        let (diff, mismatches) = diff_frames(&expected, &actual);
+        if mismatches == 0 {
+            return Ok(());
+        }
+        let _ = fs::create_dir_all(OUTPUT_DIR);
+        let actual_file = format!("{}/{}-actual.png", OUTPUT_DIR, self.name);
+        let diff_file = format!("{}/{}-diff.png", OUTPUT_DIR, self.name);
+        let _ = save_png(&actual_file, GAME_WIDTH, GAME_HEIGHT, &actual);
+        let _ = save_png(&diff_file, GAME_WIDTH, GAME_HEIGHT, &diff);
+        Err(format!(
+            "{} pixels differ from {}, see {} and {}",
+            mismatches, reference_file, Path::new(&actual_file).display(), Path::new(&diff_file).display()
+        ))
+    }
+}
+
+// 0 white to 3 black, the shade boundaries sit between the usual palettes' values
+pub fn get_shade(pixel: &[u8]) -> u8 {
+    let luma = (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000;
+    match luma {
+        224.. => 0,
+        130..=223 => 1,
+        45..=129 => 2,
+        _ => 3,
+    }
+}
+
+// matching pixels are faded out, the ones that differ are red
+pub fn diff_frames(expected: &[u8], actual: &[u8]) -> (Vec<u8>, usize) {
+    let mut diff = Vec::with_capacity(actual.len());
+    let mut mismatches = 0;
+    for (expected_px, actual_px) in expected.chunks(4).zip(actual.chunks(4)) {
+        if get_shade(expected_px) == get_shade(actual_px) {
+            let faded = 192 + actual_px[0] / 4;
+            diff.extend_from_slice(&[faded, faded, faded, 0xFF]);
+        } else {
+            mismatches += 1;
+            diff.extend_from_slice(&[0xFF, 0x00, 0x00, 0xFF]);
+        }
+    }
+    (diff, mismatches)
+}
@@ -42,6 +42,8 @@ pub struct Registers {
     pc: u16,
 }
 
+crate::impl_state!(Registers { a, b, c, d, e, f, h, l, sp, pc });
+
 impl Registers {
     pub fn new() -> Self {
         Registers {
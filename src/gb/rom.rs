@@ -36,6 +36,8 @@ pub enum RomBankMode {
     Advanced,
 }
 
+crate::impl_state_enum!(RomBankMode { Simple, Advanced });
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum RomSize {
     Zero,
@@ -72,7 +74,15 @@ pub struct Rom {
 
 impl Rom {
     pub fn new(file: &str) -> Self {
-        let data = fs::read(file).expect("Unable to read Rom file in Rom::new()");
+        Rom::load(file).expect("Unable to read Rom file in Rom::new()")
+    }
+
+    pub fn load(file: &str) -> std::io::Result<Self> {
+        let data = fs::read(file)?;
+        // the header ends at 0x14F
+        if data.len() < 0x150 {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, format!("{} is too small to be a rom", file)));
+        }
         let mut rom = Rom {
             data,
             rom_type: RomType::None,
@@ -126,8 +136,42 @@ impl Rom {
         rom.rom_type = rom_type;
         rom.rom_size = rom_size;
         rom.ram_size = ram_size;
-        rom
+        Ok(rom)
+
+    }
+
+    // carts with a battery keep their ram when switched off, that's what goes in the .sav
+    pub fn has_battery(&self) -> bool {
+        matches!(
+            self.rom_type,
+            RomType::MBC1_RAM_BATT | RomType::MBC2_BATT | RomType::MBC3_RAM_BATT | RomType::MBC3_RAM_BATT_RTC
+                | RomType::MBC3_BATT_RTC | RomType::MBC5_RAM_BATT | RomType::MBC5_RAM_BATT_RTC | RomType::MBC5_BATT_RTC
+        )
+    }
+
+    pub fn get_ram_bytes(&self) -> usize {
+        match self.rom_type {
+            // mbc2 has 512 half bytes built in and the header says 0
+            RomType::MBC2 | RomType::MBC2_BATT => 512,
+            _ => match self.ram_size {
+                RamSize::Zero => 0,
+                RamSize::KB_2 => 0x800,
+                RamSize::KB_8 => 0x2000,
+                RamSize::KB_32 => 0x8000,
+                RamSize::KB_64 => 0x10000,
+                RamSize::KB_128 => 0x20000,
+            },
+        }
+    }
+
+    // CGB flag at 0x143, 0x80 runs on both and 0xC0 is color only, older carts have part of the title there
+    pub fn is_cgb(&self) -> bool {
+        self.data[0x143] & 0x80 != 0
+    }
 
+    // SGB flag at 0x146, the snes only listens when it's 03 and the old licensee code at 0x14B is 33
+    pub fn is_sgb(&self) -> bool {
+        self.data[0x146] == 0x03 && self.data[0x14B] == 0x33
     }
 
     pub fn get_rom_type(&self) -> RomType {
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+// save states, everything the emulation needs to carry on from exactly where it was
+// the rom isn't in one, a state only loads on the cart it came from and that's checked with its crc32
+// settings and frontend bits (debugger, tracer, recordings, speed) stay whatever the loading emu has
+//
+// every field goes out in the order impl_state! lists it, little endian, no names or padding
+// STATE_VERSION goes up whenever a listed field is added, removed or moved
+// the whole thing is packbits compressed, the memories are mostly runs of the same byte
+
+pub const STATE_MAGIC: &[u8; 4] = b"GBST";
+pub const STATE_VERSION: u8 = 1;
+
+pub trait State {
+    fn write_state(&self, out: &mut Vec<u8>);
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String>;
+}
+
+pub struct StateReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        StateReader { data, pos: 0 }
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self.data.get(self.pos..self.pos + len).ok_or("save state ends early")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pos == self.data.len()
+    }
+}
+
+// impl_state!(Type { field, field, .. }) writes and reads the listed fields, anything left out isn't part of a state
+// it has to be used in the module that declares the type so private fields can be listed
+#[macro_export]
+macro_rules! impl_state {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::gb::savestate::State for $type {
+            fn write_state(&self, out: &mut Vec<u8>) {
+                $( $crate::gb::savestate::State::write_state(&self.$field, out); )*
+            }
+
+            fn read_state(&mut self, input: &mut $crate::gb::savestate::StateReader) -> Result<(), String> {
+                $( $crate::gb::savestate::State::read_state(&mut self.$field, input)?; )*
+                Ok(())
+            }
+        }
+    };
+}
+
+// enums without data, stored as the variant's index in the list
+#[macro_export]
+macro_rules! impl_state_enum {
+    ($type:ty { $($variant:ident),* $(,)? }) => {
+        impl $crate::gb::savestate::State for $type {
+            fn write_state(&self, out: &mut Vec<u8>) {
+                let idx = [$(<$type>::$variant),*].iter().position(|variant| variant == self).unwrap();
+                out.push(idx as u8);
+            }
+
+            fn read_state(&mut self, input: &mut $crate::gb::savestate::StateReader) -> Result<(), String> {
+                let idx = input.take(1)?[0] as usize;
+                *self = *[$(<$type>::$variant),*].get(idx).ok_or_else(|| format!("bad {} {} in save state", stringify!($type), idx))?;
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! impl_state_int {
+    ($($type:ty),*) => {
+        $(
+            impl State for $type {
+                fn write_state(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+                    *self = <$type>::from_le_bytes(input.take(std::mem::size_of::<$type>())?.try_into().unwrap());
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_state_int!(u8, u16, u32, u64);
+
+impl State for bool {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        *self = match input.take(1)?[0] {
+            0 => false,
+            1 => true,
+            byte => return Err(format!("bad bool {} in save state", byte)),
+        };
+        Ok(())
+    }
+}
+
+impl<T: State, const N: usize> State for [T; N] {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        for item in self {
+            item.write_state(out);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        for item in self {
+            item.read_state(input)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: State + Default> State for Vec<T> {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).write_state(out);
+        for item in self {
+            item.write_state(out);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        let mut len = 0u32;
+        len.read_state(input)?;
+        self.clear();
+        self.resize_with(len as usize, T::default);
+        for item in self {
+            item.read_state(input)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: State + Default> State for VecDeque<T> {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).write_state(out);
+        for item in self {
+            item.write_state(out);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        let mut len = 0u32;
+        len.read_state(input)?;
+        self.clear();
+        self.resize_with(len as usize, T::default);
+        for item in self {
+            item.read_state(input)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: State> State for Box<T> {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        (**self).write_state(out);
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        (**self).read_state(input)
+    }
+}
+
+impl<T: State + Default> State for Option<T> {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        self.is_some().write_state(out);
+        if let Some(item) = self {
+            item.write_state(out);
+        }
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        let mut is_some = false;
+        is_some.read_state(input)?;
+        if !is_some {
+            *self = None;
+            return Ok(());
+        }
+        self.get_or_insert_with(T::default).read_state(input)
+    }
+}
+
+// packbits, a header n of 0-127 is followed by n + 1 bytes as they are, 129-255 by one byte repeated 257 - n times
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        let run = data[pos..].iter().take(128).take_while(|&&byte| byte == data[pos]).count();
+        if run >= 3 {
+            flush_literal(&mut out, &data[literal_start..pos]);
+            out.push((257 - run) as u8);
+            out.push(data[pos]);
+            pos += run;
+            literal_start = pos;
+        } else {
+            pos += 1;
+        }
+    }
+    flush_literal(&mut out, &data[literal_start..]);
+    out
+}
+
+fn flush_literal(out: &mut Vec<u8>, literal: &[u8]) {
+    for chunk in literal.chunks(128) {
+        out.push(chunk.len() as u8 - 1);
+        out.extend_from_slice(chunk);
+    }
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut input = StateReader::new(data);
+    while !input.is_done() {
+        let header = input.take(1)?[0];
+        match header {
+            0..=127 => out.extend_from_slice(input.take(header as usize + 1)?),
+            128 => return Err(String::from("bad packbits header 128 in save state")),
+            _ => {
+                let byte = input.take(1)?[0];
+                out.resize(out.len() + 257 - header as usize, byte);
+            },
+        }
+    }
+    Ok(out)
+}
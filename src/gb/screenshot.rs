@@ -0,0 +1,92 @@
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::gb::png::save_png;
+
+// screenshots of the game and viewer windows
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScreenshotSource {
+    Game,
+    Tiles,
+    BGMap,
+}
+
+impl ScreenshotSource {
+    // same sizes as the windows' buffers
+    pub fn get_size(&self) -> (u32, u32) {
+        match self {
+            ScreenshotSource::Game => (160, 144),
+            ScreenshotSource::Tiles => (128, 128),
+            ScreenshotSource::BGMap => (256, 256),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ScreenshotSource::Game => "game",
+            ScreenshotSource::Tiles => "tiles",
+            ScreenshotSource::BGMap => "bgmap",
+        }
+    }
+}
+
+// saves the buffer as gbemu-<source>-<utc time>.png in the current directory and returns the name
+// scale repeats every pixel scale x scale times, 1 is native
+pub fn save_screenshot(source: ScreenshotSource, rgba: &[u8], scale: u32) -> io::Result<String> {
+    let (width, height) = source.get_size();
+    let scale = scale.max(1);
+    let file = format!("gbemu-{}-{}.png", source.get_name(), get_timestamp());
+    let scaled = scale_rgba(rgba, width, height, scale);
+    save_png(&file, width * scale, height * scale, &scaled)?;
+    Ok(file)
+}
+
+// nearest neighbour, the only scaling that keeps pixel art sharp
+pub fn scale_rgba(rgba: &[u8], width: u32, height: u32, scale: u32) -> Vec<u8> {
+    if scale <= 1 {
+        return rgba.to_vec();
+    }
+    let (width, height, scale) = (width as usize, height as usize, scale as usize);
+    let mut scaled = Vec::with_capacity(rgba.len() * scale * scale);
+    for y in 0..height {
+        let row = &rgba[y * width * 4..(y + 1) * width * 4];
+        let mut scaled_row = Vec::with_capacity(row.len() * scale);
+        for px in row.chunks(4) {
+            for _ in 0..scale {
+                scaled_row.extend_from_slice(px);
+            }
+        }
+        for _ in 0..scale {
+            scaled.extend_from_slice(&scaled_row);
+        }
+    }
+    scaled
+}
+
+// 20261019-153012-123, utc so there's no timezone database to worry about
+pub fn get_timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_in_day = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year, month, day, secs_in_day / 3600, (secs_in_day / 60) % 60, secs_in_day % 60, since_epoch.subsec_millis()
+    )
+}
+
+// days since 1970-01-01 to year/month/day
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
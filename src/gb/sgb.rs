@@ -0,0 +1,453 @@
+use crate::gb::graphics::palette::get_rgb555_rgba_code;
+use crate::gb::joypad::Joypad;
+use crate::gb::mbc::Mbc;
+use crate::gb::savestate::{State, StateReader};
+
+// super game boy, a dmg in a snes cartridge
+// the game talks to the snes by pulsing JOYP bits 4-5: a reset pulse, then 128 bits (16 bytes, lsb first) and a 0 to end the packet
+// the snes colors the game with 4 palettes picked per 8x8 cell and draws a 256x224 border around it
+
+pub const SGB_WIDTH: u32 = 256;
+pub const SGB_HEIGHT: u32 = 224;
+pub const SGB_BUFFER_SIZE: usize = 229_376; // 256x224 rgba
+
+// where the 160x144 game sits inside the border
+const GAME_X: usize = 48;
+const GAME_Y: usize = 40;
+const CELLS_PER_ROW: usize = 20;
+const CELL_ROWS: usize = 18;
+
+// RGB555 greys, until a game sends its own palettes it looks like a dmg
+const DEFAULT_PALETTE: [u16; 4] = [0x7FFF, 0x6318, 0x318C, 0x0000];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mask {
+    None,
+    Freeze, // keeps showing the last frame
+    Black,
+    Color0, // everything in color 0
+}
+
+crate::impl_state_enum!(Mask { None, Freeze, Black, Color0 });
+
+// the snes copies 4KB out of vram on the next frame, whatever tiles the screen shows
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Transfer {
+    BorderTiles(u8), // CHR_TRN, 0 is tiles 00-7F and 1 is 80-FF
+    BorderMap, // PCT_TRN, the map and palettes 4-7
+}
+
+pub struct Sgb {
+    pub is_enabled: bool, // the snes ignores packets from carts whose header doesn't ask for sgb functions
+    pub joyp: u8, // JOYP bits 4-5 as last written
+    pub packet: [u8; 16],
+    pub packet_bits: Option<u8>, // bits of the packet coming in so far, None between packets
+    pub packets: Vec<u8>, // a command is 1-7 packets, this holds them until the last one is in
+    pub palettes: [[u16; 4]; 4], // RGB555, color 0 is shared by all 4
+    pub attributes: [u8; 360], // palette of each 8x8 cell of the game, 20x18
+    pub mask: Mask,
+    pub players: u8, // 1, 2 or 4 from MLT_REQ
+    pub player: u8, // the one JOYP reads, 0 is player 1
+    pub pending_transfer: Option<Transfer>,
+    pub border_tiles: Vec<u8>, // 256 snes 4bpp tiles of 32 bytes
+    pub border_map: Vec<u16>, // 32x32, the bottom 4 rows are off screen
+    pub border_palettes: [[u16; 16]; 4], // palettes 4-7, the border uses them
+    frozen: Option<Vec<u8>>, // the game's shades when MASK_EN froze it
+}
+
+// written out by hand for pending_transfer, a byte that's 0 for none, 1 for PCT_TRN and 2 + n for CHR_TRN n
+impl State for Sgb {
+    fn write_state(&self, out: &mut Vec<u8>) {
+        self.is_enabled.write_state(out);
+        self.joyp.write_state(out);
+        self.packet.write_state(out);
+        self.packet_bits.write_state(out);
+        self.packets.write_state(out);
+        self.palettes.write_state(out);
+        self.attributes.write_state(out);
+        self.mask.write_state(out);
+        self.players.write_state(out);
+        self.player.write_state(out);
+        let transfer = match self.pending_transfer {
+            None => 0u8,
+            Some(Transfer::BorderMap) => 1,
+            Some(Transfer::BorderTiles(n)) => 2 + n,
+        };
+        transfer.write_state(out);
+        self.border_tiles.write_state(out);
+        self.border_map.write_state(out);
+        self.border_palettes.write_state(out);
+        self.frozen.write_state(out);
+    }
+
+    fn read_state(&mut self, input: &mut StateReader) -> Result<(), String> {
+        self.is_enabled.read_state(input)?;
+        self.joyp.read_state(input)?;
+        self.packet.read_state(input)?;
+        self.packet_bits.read_state(input)?;
+        self.packets.read_state(input)?;
+        self.palettes.read_state(input)?;
+        self.attributes.read_state(input)?;
+        self.mask.read_state(input)?;
+        self.players.read_state(input)?;
+        self.player.read_state(input)?;
+        let mut transfer = 0u8;
+        transfer.read_state(input)?;
+        self.pending_transfer = match transfer {
+            0 => None,
+            1 => Some(Transfer::BorderMap),
+            n => Some(Transfer::BorderTiles(n - 2)),
+        };
+        self.border_tiles.read_state(input)?;
+        self.border_map.read_state(input)?;
+        self.border_palettes.read_state(input)?;
+        self.frozen.read_state(input)
+    }
+}
+
+impl Default for Sgb {
+    fn default() -> Self {
+        Sgb::new(false)
+    }
+}
+
+impl Sgb {
+    pub fn new(is_enabled: bool) -> Self {
+        Sgb {
+            is_enabled,
+            joyp: 0x30,
+            packet: [0; 16],
+            packet_bits: None,
+            packets: Vec::new(),
+            palettes: [DEFAULT_PALETTE; 4],
+            attributes: [0; 360],
+            mask: Mask::None,
+            players: 1,
+            player: 0,
+            pending_transfer: None,
+            border_tiles: vec![0; 0x2000],
+            border_map: vec![0; 1024],
+            border_palettes: [[0; 16]; 4],
+            frozen: None,
+        }
+    }
+
+    // every JOYP write goes through here as well as to the joypad
+    pub fn write_joyp(&mut self, byte: u8) {
+        let before = self.joyp;
+        self.joyp = byte & 0x30;
+        match (before, self.joyp) {
+            // both low resets, a new packet starts
+            (_, 0x00) => {
+                self.packet = [0; 16];
+                self.packet_bits = Some(0);
+            },
+            // P14 low is a 0, P15 low a 1, each one goes back to 0x30 before the next
+            (0x30, 0x10) | (0x30, 0x20) => {
+                if let Some(bits) = self.packet_bits {
+                    self.receive_bit(bits, self.joyp == 0x10);
+                }
+            },
+            // P15 going back high moves on to the next player
+            (0x10, 0x30) if self.packet_bits.is_none() && self.players > 1 => {
+                self.player = (self.player + 1) % self.players;
+            },
+            _ => {},
+        }
+    }
+
+    // with more than one player and no row selected JOYP reads 0xF minus the player
+    // the other players' pads aren't plugged in, nothing is ever held on them
+    pub fn read_joyp(&self, joypad: &Joypad) -> u8 {
+        if self.players > 1 {
+            if joypad.select == 0x30 {
+                return 0xC0 | 0x30 | (0x0F - self.player);
+            }
+            if self.player != 0 {
+                return 0xC0 | joypad.select | 0x0F;
+            }
+        }
+        joypad.read()
+    }
+
+    fn receive_bit(&mut self, bits: u8, is_one: bool) {
+        if bits == 128 {
+            // the stop bit, a 1 there means the packet got garbled
+            self.packet_bits = None;
+            if !is_one {
+                self.receive_packet();
+            }
+            return;
+        }
+        if is_one {
+            self.packet[(bits / 8) as usize] |= 1 << (bits % 8);
+        }
+        self.packet_bits = Some(bits + 1);
+    }
+
+    fn receive_packet(&mut self) {
+        self.packets.extend_from_slice(&self.packet);
+        // the first packet's low 3 bits say how many make up the command
+        let length = (self.packets[0] & 0b111).max(1) as usize;
+        if self.packets.len() < length * 16 {
+            return;
+        }
+        let data = std::mem::take(&mut self.packets);
+        if self.is_enabled {
+            self.run_command(&data);
+        }
+    }
+
+    pub fn run_command(&mut self, data: &[u8]) {
+        let command = data[0] >> 3;
+        match command {
+            0x00 => self.set_palettes(0, 1, data), // PAL01
+            0x01 => self.set_palettes(2, 3, data), // PAL23
+            0x02 => self.set_palettes(0, 3, data), // PAL03
+            0x03 => self.set_palettes(1, 2, data), // PAL12
+            0x04 => self.attr_blk(data),
+            0x05 => self.attr_lin(data),
+            0x06 => self.attr_div(data),
+            0x07 => self.attr_chr(data),
+            0x11 => {
+                // MLT_REQ, 0 is one player, 1 two and 3 four
+                self.players = match data[1] & 0b11 {
+                    1 => 2,
+                    3 => 4,
+                    _ => 1,
+                };
+                self.player = 0;
+            },
+            0x13 => self.pending_transfer = Some(Transfer::BorderTiles(data[1] & 1)), // CHR_TRN
+            0x14 => self.pending_transfer = Some(Transfer::BorderMap), // PCT_TRN
+            0x17 => {
+                // MASK_EN
+                self.mask = match data[1] & 0b11 {
+                    1 => Mask::Freeze,
+                    2 => Mask::Black,
+                    3 => Mask::Color0,
+                    _ => Mask::None,
+                };
+                self.frozen = None;
+            },
+            // sound, attribute files and the rest aren't emulated, games send some of them every frame
+            _ => {},
+        }
+    }
+
+    fn get_color(data: &[u8], idx: usize) -> u16 {
+        u16::from_le_bytes([data[idx], data[idx + 1]])
+    }
+
+    // color 0, colors 1-3 of the first palette, then colors 1-3 of the second
+    fn set_palettes(&mut self, first: usize, second: usize, data: &[u8]) {
+        let color_0 = Sgb::get_color(data, 1);
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color_0;
+        }
+        for color in 1..4 {
+            self.palettes[first][color] = Sgb::get_color(data, 1 + color * 2);
+            self.palettes[second][color] = Sgb::get_color(data, 7 + color * 2);
+        }
+    }
+
+    fn set_attribute(&mut self, x: usize, y: usize, palette: u8) {
+        if x < CELLS_PER_ROW && y < CELL_ROWS {
+            self.attributes[y * CELLS_PER_ROW + x] = palette & 0b11;
+        }
+    }
+
+    // rectangles, each one can color its inside, its edge and everything outside it
+    fn attr_blk(&mut self, data: &[u8]) {
+        let count = (data[1] & 0x1F) as usize;
+        for block in data[2..].chunks_exact(6).take(count) {
+            let control = block[0] & 0b111;
+            let palettes = block[1];
+            let (x1, y1, x2, y2) = ((block[2] & 0x1F) as usize, (block[3] & 0x1F) as usize, (block[4] & 0x1F) as usize, (block[5] & 0x1F) as usize);
+            let inside = palettes & 0b11;
+            let outside = (palettes >> 4) & 0b11;
+            // with only the inside or only the outside asked for, the edge goes along with it
+            let (is_edge_set, edge) = match control {
+                0b001 => (true, inside),
+                0b100 => (true, outside),
+                _ => (control & 0b010 != 0, (palettes >> 2) & 0b11),
+            };
+            for y in 0..CELL_ROWS {
+                for x in 0..CELLS_PER_ROW {
+                    let is_in_rect = (x1..=x2).contains(&x) && (y1..=y2).contains(&y);
+                    let is_edge = is_in_rect && (x == x1 || x == x2 || y == y1 || y == y2);
+                    if is_edge {
+                        if is_edge_set {
+                            self.set_attribute(x, y, edge);
+                        }
+                    } else if is_in_rect {
+                        if control & 0b001 != 0 {
+                            self.set_attribute(x, y, inside);
+                        }
+                    } else if control & 0b100 != 0 {
+                        self.set_attribute(x, y, outside);
+                    }
+                }
+            }
+        }
+    }
+
+    // whole rows or columns, bit 7 set is a row
+    fn attr_lin(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+        for &line in data[2..].iter().take(count) {
+            let position = (line & 0x1F) as usize;
+            let palette = (line >> 5) & 0b11;
+            if line & 0x80 != 0 {
+                for x in 0..CELLS_PER_ROW {
+                    self.set_attribute(x, position, palette);
+                }
+            } else {
+                for y in 0..CELL_ROWS {
+                    self.set_attribute(position, y, palette);
+                }
+            }
+        }
+    }
+
+    // splits the screen at a row (bit 6 set) or a column, one palette before it, one on it and one after
+    fn attr_div(&mut self, data: &[u8]) {
+        let after = data[1] & 0b11;
+        let before = (data[1] >> 2) & 0b11;
+        let on_line = (data[1] >> 4) & 0b11;
+        let is_row = data[1] & 0x40 != 0;
+        let line = (data[2] & 0x1F) as usize;
+        for y in 0..CELL_ROWS {
+            for x in 0..CELLS_PER_ROW {
+                let position = if is_row { y } else { x };
+                let palette = if position < line { before } else if position == line { on_line } else { after };
+                self.set_attribute(x, y, palette);
+            }
+        }
+    }
+
+    // one palette per cell, 4 to a byte from the top bits down, filling rows or columns from a start cell
+    fn attr_chr(&mut self, data: &[u8]) {
+        let (mut x, mut y) = ((data[1] & 0x1F) as usize, (data[2] & 0x1F) as usize);
+        let count = (u16::from_le_bytes([data[3], data[4]]) as usize).min(CELLS_PER_ROW * CELL_ROWS);
+        let is_down = data[5] & 1 != 0;
+        for i in 0..count {
+            let Some(&byte) = data.get(6 + i / 4) else { break; };
+            if x >= CELLS_PER_ROW || y >= CELL_ROWS {
+                break;
+            }
+            self.set_attribute(x, y, byte >> (6 - 2 * (i % 4)));
+            if is_down {
+                y += 1;
+                if y == CELL_ROWS {
+                    y = 0;
+                    x += 1;
+                }
+            } else {
+                x += 1;
+                if x == CELLS_PER_ROW {
+                    x = 0;
+                    y += 1;
+                }
+            }
+        }
+    }
+
+    // the 4KB on screen, 256 tiles in map order at 20 per row
+    fn read_vram_transfer(mbc: &Mbc) -> Vec<u8> {
+        let map = if mbc.hw_reg.is_lcdc_bg_tile_map_bit3_enabled() { 0x9C00 } else { 0x9800 };
+        let mut data = Vec::with_capacity(0x1000);
+        for i in 0..256u16 {
+            let tile_num = mbc.read_vram(0, map + (i / 20) * 32 + i % 20);
+            let tile_address = if mbc.hw_reg.is_lcdc_bg_win_tile_data_area_bit4_enabled() {
+                0x8000 + tile_num as u16 * 16
+            } else {
+                (0x9000 + (tile_num as i8 as i32) * 16) as u16
+            };
+            for offset in 0..16 {
+                data.push(mbc.read_vram(0, tile_address + offset));
+            }
+        }
+        data
+    }
+
+    fn finish_transfer(&mut self, transfer: Transfer, data: &[u8]) {
+        match transfer {
+            Transfer::BorderTiles(half) => {
+                let start = half as usize * 0x1000;
+                self.border_tiles[start..start + 0x1000].copy_from_slice(data);
+            },
+            Transfer::BorderMap => {
+                for (i, entry) in self.border_map.iter_mut().enumerate() {
+                    *entry = Sgb::get_color(data, i * 2);
+                }
+                for (i, palette) in self.border_palettes.iter_mut().enumerate() {
+                    for (color, value) in palette.iter_mut().enumerate() {
+                        *value = Sgb::get_color(data, 0x800 + i * 32 + color * 2);
+                    }
+                }
+            },
+        }
+    }
+
+    // runs at the end of every frame, takes any vram transfer that was asked for and draws the screen
+    // shades is the ppu's 160x144 shade buffer, 0-3 per pixel after BGP/OBP0/OBP1 like the dmg lcd gets them
+    pub fn end_frame(&mut self, mbc: &Mbc, shades: &[u8], screen: &mut [u8]) {
+        if let Some(transfer) = self.pending_transfer.take() {
+            self.finish_transfer(transfer, &Sgb::read_vram_transfer(mbc));
+        }
+        if self.mask == Mask::Freeze && self.frozen.is_none() {
+            self.frozen = Some(shades.to_vec());
+        }
+        self.draw(shades, screen);
+    }
+
+    fn draw(&self, shades: &[u8], screen: &mut [u8]) {
+        let width = SGB_WIDTH as usize;
+        let backdrop = get_rgb555_rgba_code(self.palettes[0][0]);
+        for px in screen.chunks_exact_mut(4) {
+            px.copy_from_slice(&backdrop);
+        }
+
+        let shades = self.frozen.as_deref().unwrap_or(shades);
+        for y in 0..144 {
+            for x in 0..160 {
+                let color = match self.mask {
+                    Mask::Black => 0x0000,
+                    Mask::Color0 => self.palettes[0][0],
+                    Mask::None | Mask::Freeze => {
+                        let palette = self.attributes[(y / 8) * CELLS_PER_ROW + x / 8] as usize;
+                        self.palettes[palette][(shades[y * 160 + x] & 0b11) as usize]
+                    },
+                };
+                let idx = ((GAME_Y + y) * width + GAME_X + x) * 4;
+                screen[idx..idx + 4].copy_from_slice(&get_rgb555_rgba_code(color));
+            }
+        }
+
+        // border tiles go over the game, color 0 is see through
+        for tile_y in 0..(SGB_HEIGHT as usize / 8) {
+            for tile_x in 0..(width / 8) {
+                // bits 0-7 tile, 10-12 palette, 14 x flip, 15 y flip
+                let entry = self.border_map[tile_y * 32 + tile_x];
+                let tile = &self.border_tiles[(entry & 0xFF) as usize * 32..][..32];
+                let palette = &self.border_palettes[((entry >> 10) & 0b11) as usize];
+                for row in 0..8 {
+                    let tile_row = if entry & 0x8000 != 0 { 7 - row } else { row };
+                    // 4 bit planes, 0-1 in the first 16 bytes and 2-3 in the rest
+                    let planes = [tile[tile_row * 2], tile[tile_row * 2 + 1], tile[16 + tile_row * 2], tile[16 + tile_row * 2 + 1]];
+                    for col in 0..8 {
+                        let bit = if entry & 0x4000 != 0 { col } else { 7 - col };
+                        let color_id = planes.iter().enumerate()
+                            .fold(0, |id, (plane, byte)| id | (((byte >> bit) & 1) as usize) << plane);
+                        if color_id == 0 {
+                            continue;
+                        }
+                        let idx = ((tile_y * 8 + row) * width + tile_x * 8 + col) * 4;
+                        screen[idx..idx + 4].copy_from_slice(&get_rgb555_rgba_code(palette[color_id]));
+                    }
+                }
+            }
+        }
+    }
+}
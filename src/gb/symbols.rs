@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+// labels from a .sym file next to the rom, the format rgbds and wlalink both write:
+// 01:48fd memcpy
+// lines starting with ; are comments, wlalink also adds [sections] and only [labels] has labels
+
+pub struct Symbols {
+    by_address: BTreeMap<(u8, u16), String>,
+    by_name: HashMap<String, (u8, u16)>,
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Symbols {
+            by_address: BTreeMap::new(),
+            by_name: HashMap::new(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut symbols = Symbols::new();
+        let mut is_label_section = true;
+        for line in text.lines() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                is_label_section = line == "[labels]";
+                continue;
+            }
+            if !is_label_section {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (Some(location), Some(name)) = (parts.next(), parts.next()) else { continue; };
+            let Some((bank, address)) = location.split_once(':') else { continue; };
+            let (Ok(bank), Ok(address)) = (u8::from_str_radix(bank, 16), u16::from_str_radix(address, 16)) else { continue; };
+            symbols.insert(bank, address, name);
+        }
+        symbols
+    }
+
+    pub fn load(file: &str) -> std::io::Result<Self> {
+        Ok(Symbols::parse(&fs::read_to_string(file)?))
+    }
+
+    // game.gb -> game.sym, None if there isn't one
+    pub fn load_for_rom(rom_file: &str) -> Option<Self> {
+        let sym_file = Path::new(rom_file).with_extension("sym");
+        let symbols = Symbols::load(sym_file.to_str()?).ok()?;
+        println!("loaded {} labels from {}", symbols.len(), sym_file.display());
+        Some(symbols)
+    }
+
+    pub fn insert(&mut self, bank: u8, address: u16, name: &str) {
+        // the first label at an address wins, later ones are usually local aliases
+        self.by_address.entry((bank, address)).or_insert_with(|| String::from(name));
+        self.by_name.insert(String::from(name), (bank, address));
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    pub fn get_label(&self, bank: u8, address: u16) -> Option<&str> {
+        self.by_address.get(&(bank, address)).map(|name| name.as_str())
+    }
+
+    pub fn get_address(&self, name: &str) -> Option<(u8, u16)> {
+        self.by_name.get(name).copied()
+    }
+
+    // nearest label at or before the address in the same bank, eg. quit@halt+1
+    pub fn describe(&self, bank: u8, address: u16) -> Option<String> {
+        let ((label_bank, label_address), name) = self.by_address.range(..=(bank, address)).next_back()?;
+        if *label_bank != bank {
+            return None;
+        }
+        let offset = address - label_address;
+        if offset == 0 {
+            Some(name.clone())
+        } else {
+            Some(format!("{}+{}", name, offset))
+        }
+    }
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Symbols::new()
+    }
+}
@@ -1,11 +1,16 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Result;
-use std::error::Error;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
 use std::fs;
 use crate::gb::registers::Registers;
+use crate::gb::cpu::Cpu;
+use crate::gb::mbc::{Mbc, OpSource};
+use crate::gb::bus::{Bus, BusCycle};
+
+// single step tests from https://github.com/SingleStepTests/sm83, one file per opcode in tests/
+// each test sets up registers and ram, runs one instruction and checks registers, ram and
+// what happened on the bus every mcycle
+// cargo test --test cpu_json runs them, cargo test --test cpu_json op_3e runs just LD A,d8
 
 #[derive(Debug)]
 pub enum FailedRegister {
@@ -49,6 +54,16 @@ pub fn setup_initial_registers(registers: &mut Registers, initial_test_state: &T
 pub fn compare_registers(registers: &Registers, final_test_state: &TestState) -> Vec<FailedRegister> {
     let mut failed_registers: Vec<FailedRegister> = Vec::new();
 
+    let pc = registers.get_pc();
+    if pc != final_test_state.pc {
+        failed_registers.push(FailedRegister::pc(pc));
+    }
+
+    let sp = registers.get_sp();
+    if sp != final_test_state.sp {
+        failed_registers.push(FailedRegister::sp(sp));
+    }
+
     let a = registers.get_a();
     if a != final_test_state.a {
         failed_registers.push(FailedRegister::a(a));
@@ -135,6 +150,8 @@ pub struct Test {
     // final vec of TestRam
     #[serde(rename = "final")]
     pub final_test_state: TestState,
+    // one entry per mcycle: address, value, and r-m for a read, -wm for a write or --- for neither
+    pub cycles: Vec<(u16, Option<u8>, String)>,
 }
 
 // create tests and add to Vec
@@ -173,9 +190,116 @@ pub fn get_all_files_in_directory(path: &str) -> Vec<String> {
 
 pub fn get_all_tests() ->  Vec<Test> {
     let mut all_tests: Vec<Test> = Vec::new();
-    let all_files = get_all_files_in_directory("tests");
+    let mut all_files = get_all_files_in_directory("tests");
+    all_files.retain(|file| file.ends_with(".json"));
+    all_files.sort();
     for file in &all_files {
         read_test_file(file, &mut all_tests);
     }
     all_tests
 }
+
+// "3e" -> tests/3e.json, "cb 7c" -> tests/cb 7c.json
+pub fn get_test_file(opcode: &str) -> String {
+    format!("tests/{}.json", opcode)
+}
+
+fn get_expected_register(register: &FailedRegister, state: &TestState) -> String {
+    match register {
+        FailedRegister::pc(_) => format!("{:04X}", state.pc),
+        FailedRegister::sp(_) => format!("{:04X}", state.sp),
+        FailedRegister::a(_) => format!("{:02X}", state.a),
+        FailedRegister::b(_) => format!("{:02X}", state.b),
+        FailedRegister::c(_) => format!("{:02X}", state.c),
+        FailedRegister::d(_) => format!("{:02X}", state.d),
+        FailedRegister::e(_) => format!("{:02X}", state.e),
+        FailedRegister::f(_) => format!("{:02X}", state.f),
+        FailedRegister::h(_) => format!("{:02X}", state.h),
+        FailedRegister::l(_) => format!("{:02X}", state.l),
+    }
+}
+
+fn get_actual_register(register: &FailedRegister) -> String {
+    match register {
+        FailedRegister::pc(v) | FailedRegister::sp(v) => format!("{:04X}", v),
+        FailedRegister::a(v) | FailedRegister::b(v) | FailedRegister::c(v) | FailedRegister::d(v) |
+        FailedRegister::e(v) | FailedRegister::f(v) | FailedRegister::h(v) | FailedRegister::l(v) => format!("{:02X}", v),
+    }
+}
+
+fn expected_bus_cycle(cycle: &(u16, Option<u8>, String)) -> BusCycle {
+    let (address, value, kind) = cycle;
+    match kind.as_str() {
+        "r-m" => BusCycle::Read(*address, value.unwrap_or(0)),
+        "-wm" => BusCycle::Write(*address, value.unwrap_or(0)),
+        _ => BusCycle::Internal,
+    }
+}
+
+fn describe_bus_cycle(cycle: Option<&BusCycle>) -> String {
+    match cycle {
+        Some(BusCycle::Read(address, value)) => format!("read  {:04X} {:02X}", address, value),
+        Some(BusCycle::Write(address, value)) => format!("write {:04X} {:02X}", address, value),
+        Some(BusCycle::Internal) => String::from("---"),
+        None => String::from("(none)"),
+    }
+}
+
+// runs one test on a cpu and an mbc in testing mode, Err has everything that didn't match
+pub fn run_test(cpu: &mut Cpu, mbc: &mut Mbc, test: &Test) -> Result<(), String> {
+    setup_initial_registers(&mut cpu.registers, &test.initial_test_state);
+    cpu.halted = false;
+    for ram_entry in &test.initial_test_state.test_ram {
+        mbc.write(ram_entry.0, ram_entry.1, OpSource::CPU);
+    }
+
+    let mut no_op = |_: &mut Mbc| {};
+    let mut bus = Bus::new(mbc, &mut no_op);
+    bus.activity = Some(Vec::new());
+    cpu.step(&mut bus);
+    let activity = bus.activity.take().unwrap_or_default();
+
+    let mut report = Vec::new();
+    for register in compare_registers(&cpu.registers, &test.final_test_state) {
+        let name = format!("{:?}", register);
+        let name = name.split('(').next().unwrap_or("");
+        report.push(format!("  {:<2} expected {} got {}", name, get_expected_register(&register, &test.final_test_state), get_actual_register(&register)));
+    }
+    for ram_entry in &test.final_test_state.test_ram {
+        let value = mbc.read(ram_entry.0, OpSource::CPU);
+        if value != ram_entry.1 {
+            report.push(format!("  ram {:04X} expected {:02X} got {:02X}", ram_entry.0, ram_entry.1, value));
+        }
+    }
+    let expected_cycles: Vec<BusCycle> = test.cycles.iter().map(expected_bus_cycle).collect();
+    if expected_cycles != activity {
+        report.push(String::from("  bus      expected          got"));
+        for idx in 0..expected_cycles.len().max(activity.len()) {
+            let expected = expected_cycles.get(idx);
+            let actual = activity.get(idx);
+            let marker = if expected == actual { " " } else { "*" };
+            report.push(format!("  {} {:<2}     {:<16}  {}", marker, idx, describe_bus_cycle(expected), describe_bus_cycle(actual)));
+        }
+    }
+
+    // clean up ram for the next test
+    for ram_entry in test.initial_test_state.test_ram.iter().chain(test.final_test_state.test_ram.iter()) {
+        mbc.write(ram_entry.0, 0, OpSource::CPU);
+    }
+
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{}\n{}", test.name, report.join("\n")))
+    }
+}
+
+// every test in one opcode file, returns the failure reports
+pub fn run_test_file(file: &str) -> Vec<String> {
+    let mut tests = Vec::new();
+    read_test_file(file, &mut tests);
+    let mut cpu = Cpu::new();
+    let mut mbc = Mbc::new();
+    mbc.is_testing_enabled = true;
+    tests.iter().filter_map(|test| run_test(&mut cpu, &mut mbc, test).err()).collect()
+}
@@ -0,0 +1,88 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+
+use crate::gb::cpu::Cpu;
+use crate::gb::mbc::*;
+use crate::gb::symbols::Symbols;
+
+// instruction trace in the gameboy doctor format so logs can be diffed against reference runs
+// A:00 F:11 B:22 C:33 D:44 E:55 H:66 L:77 SP:8888 PC:9999 PCMEM:AA,BB,CC,DD
+// https://github.com/robert/gameboy-doctor
+
+pub struct Tracer {
+    writer: BufWriter<File>,
+    pub start_pc: u16, // nothing is logged until the cpu gets here, 0x100 skips the boot rom
+    pub limit: Option<u64>,
+    pub lines: u64,
+    pub symbols: Option<Arc<Symbols>>,
+    pub show_labels: bool, // off by default, label lines break diffing against reference logs
+    is_started: bool,
+    is_done: bool,
+}
+
+impl Tracer {
+    pub fn new(file: &str, start_pc: u16, limit: Option<u64>) -> std::io::Result<Self> {
+        let writer = BufWriter::new(File::create(file)?);
+        Ok(Tracer {
+            writer,
+            start_pc,
+            limit,
+            lines: 0,
+            symbols: None,
+            show_labels: false,
+            is_started: false,
+            is_done: false,
+        })
+    }
+
+    // called right before the cpu fetches an instruction
+    pub fn log(&mut self, cpu: &Cpu, mbc: &Mbc) {
+        if self.is_done {
+            return;
+        }
+        let pc = cpu.registers.get_pc();
+        if !self.is_started {
+            if pc != self.start_pc {
+                return;
+            }
+            self.is_started = true;
+        }
+
+        if self.show_labels {
+            if let Some(label) = self.symbols.as_ref().and_then(|symbols| symbols.get_label(mbc.get_rom_bank(pc), pc)) {
+                let _ = writeln!(self.writer, "{}:", label);
+            }
+        }
+
+        let regs = &cpu.registers;
+        let pcmem: Vec<String> = (0..4)
+            .map(|i| format!("{:02X}", mbc.read(pc.wrapping_add(i), OpSource::Debugger)))
+            .collect();
+        let line = format!(
+            "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{}\n",
+            regs.get_a(), regs.get_f(), regs.get_b(), regs.get_c(), regs.get_d(), regs.get_e(),
+            regs.get_h(), regs.get_l(), regs.get_sp(), pc, pcmem.join(",")
+        );
+        if self.writer.write_all(line.as_bytes()).is_err() {
+            println!("unable to write trace, stopping it");
+            self.is_done = true;
+            return;
+        }
+        self.lines += 1;
+
+        // Emu::shut_down flushes the rest, this is for a process that gets killed instead
+        if self.lines.is_multiple_of(1000) {
+            let _ = self.writer.flush();
+        }
+        if self.limit == Some(self.lines) {
+            let _ = self.writer.flush();
+            self.is_done = true;
+            println!("trace finished after {} instructions", self.lines);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
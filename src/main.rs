@@ -17,19 +17,130 @@ use std::thread;
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::{Duration, Instant};
 
-mod gb;  
+use gbemu::gb;
 
 use gb::bios::ColorMode;
-use crate::gb::emu::*;
-use crate::gb::gbwindow::*;
-use crate::gb::constants::*;
-use crate::gb::graphics::ppu::{PPUEvent, RenderState};
-use crate::gb::joypad::Joypad;
+use gb::emu::*;
+use gb::gbwindow::*;
+use gb::constants::*;
+use gb::graphics::ppu::{PPUEvent, RenderState};
+use gb::keybindings::{KeyBindings, KeyAction, Hotkey, DEFAULT_BINDINGS_FILE};
+use gb::movie::Movie;
+use gb::gdbstub::{GdbStub, DEFAULT_GDB_PORT};
+use gb::trace::Tracer;
+use gb::screenshot::{ScreenshotSource, save_screenshot};
+use gb::pacer::Speed;
+use gb::sgb::{SGB_WIDTH, SGB_HEIGHT};
 
 
 fn main() {
     //env::set_var("RUST_BACKTRACE", "1");
     env_logger::init();
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(|a| a.as_str()) == Some("disasm") {
+        gb::disasm::run_disasm_command(&args[2..]);
+        return;
+    }
+    if args.get(1).map(|a| a.as_str()) == Some("mooneye") {
+        gb::mooneye::run_mooneye_command(&args[2..]);
+        return;
+    }
+    if args.get(1).map(|a| a.as_str()) == Some("blargg") {
+        gb::blargg::run_blargg_command(&args[2..]);
+        return;
+    }
+    // --gdb [port]
+    // --trace file [--trace-start pc] [--trace-limit instructions] [--trace-labels] [--trace-stub-ly]
+    let mut gdb_port: Option<u16> = None;
+    let mut trace_file: Option<String> = None;
+    let mut trace_start: u16 = 0x100;
+    let mut trace_limit: Option<u64> = None;
+    let mut trace_labels = false;
+    let mut trace_stub_ly = false;
+    let mut screenshot_scale: u32 = 1;
+    let mut record_video: Option<String> = None;
+    let mut record_movie: Option<String> = None;
+    let mut play_movie: Option<String> = None;
+    let mut keys_file: Option<String> = None;
+    let mut speed = Speed::Times(1.0);
+    let mut rom_file = String::from("mm2.gb");
+    let mut fast_forward_speed = Speed::Unthrottled;
+    let mut color_mode: Option<ColorMode> = None;
+    let mut arg_idx = 1;
+    while arg_idx < args.len() {
+        match args[arg_idx].as_str() {
+            "--gdb" => {
+                let port = args.get(arg_idx + 1).and_then(|p| p.parse::<u16>().ok());
+                if port.is_some() {
+                    arg_idx += 1;
+                }
+                gdb_port = Some(port.unwrap_or(DEFAULT_GDB_PORT));
+            },
+            "--trace" => {
+                trace_file = args.get(arg_idx + 1).cloned();
+                arg_idx += 1;
+            },
+            "--trace-start" => {
+                let start = args.get(arg_idx + 1).map(|pc| pc.trim_start_matches("0x"));
+                match start.and_then(|pc| u16::from_str_radix(pc, 16).ok()) {
+                    Some(pc) => trace_start = pc,
+                    None => println!("--trace-start needs a hex pc"),
+                }
+                arg_idx += 1;
+            },
+            "--trace-labels" => trace_labels = true,
+            "--trace-stub-ly" => trace_stub_ly = true,
+            "--record-video" => {
+                record_video = args.get(arg_idx + 1).cloned();
+                arg_idx += 1;
+            },
+            // --speed 2, --speed 0.5, --fast-forward-speed 4x, ...
+            "--speed" | "--fast-forward-speed" => {
+                match args.get(arg_idx + 1).and_then(|s| Speed::parse(s)) {
+                    Some(parsed) if args[arg_idx] == "--speed" => speed = parsed,
+                    Some(parsed) => fast_forward_speed = parsed,
+                    None => println!("{} needs a multiplier above 0 or unthrottled", args[arg_idx]),
+                }
+                arg_idx += 1;
+            },
+            // --model dmg, --model cgb, --model sgb, otherwise the cart's header decides between dmg and cgb
+            "--model" => {
+                match args.get(arg_idx + 1).map(|m| m.as_str()) {
+                    Some("auto") => color_mode = None,
+                    Some(name) if ColorMode::from_name(name).is_some() => color_mode = ColorMode::from_name(name),
+                    _ => println!("--model needs dmg, cgb, sgb or auto"),
+                }
+                arg_idx += 1;
+            },
+            "--keys" => {
+                keys_file = args.get(arg_idx + 1).cloned();
+                arg_idx += 1;
+            },
+            "--record-movie" => {
+                record_movie = args.get(arg_idx + 1).cloned();
+                arg_idx += 1;
+            },
+            "--play-movie" => {
+                play_movie = args.get(arg_idx + 1).cloned();
+                arg_idx += 1;
+            },
+            "--screenshot-scale" => {
+                match args.get(arg_idx + 1).and_then(|n| n.parse::<u32>().ok()) {
+                    Some(scale) if scale > 0 => screenshot_scale = scale,
+                    _ => println!("--screenshot-scale needs a number above 0"),
+                }
+                arg_idx += 1;
+            },
+            "--trace-limit" => {
+                trace_limit = args.get(arg_idx + 1).and_then(|n| n.parse::<u64>().ok());
+                arg_idx += 1;
+            },
+            // gbemu [options] game.gb
+            other if !other.starts_with("--") => rom_file = String::from(other),
+            other => println!("ignoring unknown argument {}", other),
+        }
+        arg_idx += 1;
+    }
     let event_loop = EventLoop::new().unwrap();
     //event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + Duration::from_millis(16)));
     event_loop.set_control_flow(ControlFlow::Poll);
@@ -37,32 +148,93 @@ fn main() {
     // these are for quick debugging
     let skip_render = false;
     let skip_windows = false;
-    let is_debug = true; // true doesn't panic if CPU ticks take longer than a sec
+    let is_debug = true;
     /////////////////////////////////////
 
+    // keybindings.json is picked up from the current directory when --keys isn't given
+    let keys_file = keys_file.or_else(|| {
+        std::path::Path::new(DEFAULT_BINDINGS_FILE).exists().then(|| String::from(DEFAULT_BINDINGS_FILE))
+    });
+    let mut key_bindings = match keys_file {
+        Some(file) => match KeyBindings::load(&file) {
+            Ok(bindings) => {
+                println!("loaded key bindings from {}", file);
+                bindings
+            },
+            Err(e) => {
+                println!("unable to load key bindings from {}, using the defaults: {}", file, e);
+                // still watched, fixing the file picks it up
+                let mut bindings = KeyBindings::new();
+                bindings.file = Some(file);
+                bindings
+            },
+        },
+        None => KeyBindings::new(),
+    };
+
     // setup emu
-    let mut joypad = Arc::new(Mutex::new(Joypad::new()));
-    let mut joypad_arc = Arc::clone(&joypad);
-    let mut emu = Emu::new(ColorMode::Gray, joypad_arc, is_debug);
-
-    // rom is loaded after bios runs
-    //emu.load_rom_file(String::from("tamagotchi.gb"));
-    //emu.load_rom_file(String::from("tetris.gb"));
-    //emu.load_rom_file(String::from("dmg-acid2.gb"));
-    //emu.load_rom_file(String::from("daa.gb"));
-    //emu.load_rom_file(String::from("cpu_instrs.gb"));
-    //emu.load_rom_file(String::from("addams.gb"));
-    //emu.load_rom_file(String::from("drmario.gb"));
-    emu.load_rom_file(String::from("mm2.gb"));
-    emu.load_bios();
+    let mut emu = Emu::new(color_mode, is_debug);
+    emu.speed = speed;
+    emu.fast_forward_speed = fast_forward_speed;
+    emu.pacer.set_speed(speed);
+    let (emu_event_tx, emu_event_rx) = mpsc::channel();
+    emu.event_tx = Some(emu_event_tx);
+    let (emu_command_tx, emu_command_rx) = mpsc::channel();
+    emu.command_rx = Some(emu_command_rx);
+    if let Some(file) = record_video {
+        if let Err(e) = emu.start_recording(&file) {
+            println!("unable to record to {}: {}", file, e);
+        }
+    }
+    if let Some(file) = trace_file {
+        match Tracer::new(&file, trace_start, trace_limit) {
+            Ok(mut tracer) => {
+                tracer.show_labels = trace_labels;
+                emu.cpu.tracer = Some(tracer);
+                emu.mbc.is_ly_stubbed = trace_stub_ly;
+            },
+            Err(e) => println!("unable to create trace file {}: {}", file, e),
+        }
+    }
+    if let Some(port) = gdb_port {
+        match GdbStub::new(port) {
+            Ok(gdb) => emu.gdb = Some(gdb),
+            Err(e) => println!("unable to start gdb stub on port {}: {}", port, e),
+        }
+    }
+
+    // the boot rom runs first, then the rom
+    // another one can be dropped on the game window or loaded from the debugger console later
+    if let Err(e) = emu.load_rom(&rom_file) {
+        println!("unable to load rom {}: {}", rom_file, e);
+    }
+
+    // nothing has run yet, so a recording starts at power-on and a movie plays from its own start
+    if let Some(file) = play_movie {
+        match Movie::load(&file).and_then(|movie| emu.play_movie(movie)) {
+            Ok(()) => println!("playing input movie {}", file),
+            Err(e) => println!("unable to play input movie {}: {}", file, e),
+        }
+    } else if let Some(file) = record_movie {
+        if let Err(e) = emu.start_movie_recording(&file, &rom_file) {
+            println!("unable to record input movie to {}: {}", file, e);
+        }
+    }
 
 
     if !skip_windows {
+        emu.debugger.attach_console();
        //   let mut bg_map_win = GBWindow::new(WindowType::Game, &event_loop, 1024, 1024);
 
         let mut tile_win = GBWindow::new(WindowType::Tile, &event_loop, 128, 128);
         let mut bg_map_win = GBWindow::new(WindowType::BGMap, &event_loop, 256, 256);
-        let mut game_win = GBWindow::new(WindowType::Game, &event_loop, 160, 144);
+        // a super game boy shows the border around the game
+        let is_sgb = color_mode == Some(ColorMode::Super);
+        let mut game_win = if is_sgb {
+            GBWindow::new(WindowType::Game, &event_loop, SGB_WIDTH, SGB_HEIGHT)
+        } else {
+            GBWindow::new(WindowType::Game, &event_loop, 160, 144)
+        };
         //let mut game_win = GBWindow::new(WindowType::Game, &event_loop, 256, 256);
 
         let tile_win_id = tile_win.window.id();
@@ -77,6 +249,7 @@ fn main() {
         let bg_map_win_buffer = Arc::new(Mutex::new(vec![0u8; 262_144]));
 
         let game_win_buffer = Arc::new(Mutex::new(vec![0u8; 92_160]));
+        let sgb_buffer = Arc::clone(&emu.sgb_buffer);
         //let game_win_buffer = Arc::new(Mutex::new(vec![0u8; 262_144]));
 
         let mut render_state = Arc::new(Mutex::new(PPUEvent::RenderEvent(RenderState::Render)));
@@ -84,8 +257,8 @@ fn main() {
         let mut tile_win_buffer_arc = Arc::clone(&tile_win_buffer);
         let mut bg_map_win_buffer_arc = Arc::clone(&bg_map_win_buffer);
         let mut game_win_buffer_arc = Arc::clone(&game_win_buffer);
-        thread::spawn(move || {
-            loop {
+        let emu_thread = thread::spawn(move || {
+            while !emu.is_shut_down {
                 let mut rs = render_state_arc.lock().unwrap();
                 *rs = emu.tick(&tile_win_buffer_arc, &bg_map_win_buffer_arc, &game_win_buffer_arc);
             }
@@ -101,6 +274,7 @@ fn main() {
         let tw_max_fps = 10;
         let bgmw_max_fps = 10;
         let gw_max_fps = 60;
+        let mut key_bindings_check_time = Instant::now();
 
         event_loop.run(|event, elwt| {
             let mut render_state_cloned = PPUEvent::RenderEvent(RenderState::Render);
@@ -108,6 +282,19 @@ fn main() {
                 //let mut rs = render_state.lock().unwrap();
                 //render_state_cloned = *rs;
             }
+            if key_bindings_check_time.elapsed().as_secs() >= one_sec {
+                key_bindings.reload_if_changed();
+                key_bindings_check_time = Instant::now();
+            }
+            while let Ok(emu_event) = emu_event_rx.try_recv() {
+                match emu_event {
+                    EmuEvent::LockUp(lock_up) => {
+                        println!("emulation stopped, CPU locked up on opcode 0x{:02X} at {:02X}:{:04X}", lock_up.opcode, lock_up.bank, lock_up.pc);
+                        game_win.window.set_title(&format!("GBEMU Game - locked up at {:02X}:{:04X}", lock_up.bank, lock_up.pc));
+                    },
+                    EmuEvent::Reset => game_win.window.set_title("GBEMU Game"),
+                }
+            }
             let cloned_event = event.clone();
             let mut cloned_window_id = WindowId::clone(&tile_win_id);
 
@@ -118,29 +305,61 @@ fn main() {
                     cloned_window_id = window_id.clone();
                     match win_event {
                         WindowEvent::KeyboardInput {event: key_event, ..} => {
-
-                            // I only need to handle key presses for the game window atm
-                            if window_id == game_win_id {
-                                println!("matched game_win_id");
-                                match key_event {
-                                    KeyEvent {
-                                        physical_key,
-                                        state,
-                                        ..
-                                    } => {
-                                        match physical_key {
-                                            PhysicalKey::Code(key) => {
-                                                let mut joypad_unlocked = joypad.lock().unwrap();
-                                                joypad_unlocked.handle_input(key, state);
-                                            },
-                                            PhysicalKey::Unidentified(native_key_code) => {
-                                                println!("Unidentified key pressed");
-                                            }
-
+                            let PhysicalKey::Code(key) = key_event.physical_key else {
+                                println!("Unidentified key pressed");
+                                return;
+                            };
+                            let is_pressed = key_event.state == ElementState::Pressed;
+                            match key_bindings.get_action(key) {
+                                // held, not toggled
+                                Some(KeyAction::Hotkey(Hotkey::FastForward)) if !key_event.repeat => {
+                                    let _ = emu_command_tx.send(EmuCommand::SetFastForward(is_pressed));
+                                },
+                                Some(KeyAction::Hotkey(hotkey)) if is_pressed && !key_event.repeat => match hotkey {
+                                    // saves whichever window has focus
+                                    Hotkey::Screenshot => {
+                                        let (source, buffer) = if window_id == tile_win_id {
+                                            (ScreenshotSource::Tiles, &tile_win_buffer)
+                                        } else if window_id == bg_map_win_id {
+                                            (ScreenshotSource::BGMap, &bg_map_win_buffer)
+                                        } else {
+                                            (ScreenshotSource::Game, &game_win_buffer)
+                                        };
+                                        let buffer_unlocked = buffer.lock().unwrap();
+                                        match save_screenshot(source, &buffer_unlocked, screenshot_scale) {
+                                            Ok(file) => println!("saved screenshot {}", file),
+                                            Err(e) => println!("unable to save screenshot: {}", e),
                                         }
-                                    }
-                                }
-
+                                    },
+                                    Hotkey::RecordVideo => {
+                                        let _ = emu_command_tx.send(EmuCommand::ToggleRecording);
+                                    },
+                                    Hotkey::Pause => {
+                                        let _ = emu_command_tx.send(EmuCommand::TogglePause);
+                                    },
+                                    Hotkey::FrameAdvance => {
+                                        let _ = emu_command_tx.send(EmuCommand::AdvanceFrames(1));
+                                    },
+                                    Hotkey::Reset => {
+                                        let _ = emu_command_tx.send(EmuCommand::SoftReset);
+                                    },
+                                    Hotkey::HardReset => {
+                                        let _ = emu_command_tx.send(EmuCommand::HardReset);
+                                    },
+                                    Hotkey::SaveState => {
+                                        let _ = emu_command_tx.send(EmuCommand::SaveState);
+                                    },
+                                    Hotkey::LoadState => {
+                                        let _ = emu_command_tx.send(EmuCommand::LoadState);
+                                    },
+                                    Hotkey::FastForward => {},
+                                },
+                                // I only need to handle button presses for the game window atm
+                                // the emu picks them up at the end of the frame
+                                Some(KeyAction::Button(button)) if window_id == game_win_id && !key_event.repeat => {
+                                    let _ = emu_command_tx.send(EmuCommand::SetButton(button, is_pressed));
+                                },
+                                _ => {},
                             }
                         }
                         WindowEvent::RedrawRequested => {
@@ -193,6 +412,9 @@ fn main() {
                             }
 
                         },
+                        WindowEvent::DroppedFile(path) if window_id == game_win_id => {
+                            let _ = emu_command_tx.send(EmuCommand::LoadRom(path.to_string_lossy().into_owned()));
+                        },
                         WindowEvent::CloseRequested => {
                             // I can't implement a per window close yet because of how the object is used and passed around
                             // todo redo the window closing
@@ -316,7 +538,7 @@ fn main() {
             if gw_current_time.elapsed().as_secs() < one_sec  {
                 if gw_frames_this_sec < gw_max_fps {
                     {
-                        let mut gw_buffer_unlocked = game_win_buffer.lock().unwrap();
+                        let mut gw_buffer_unlocked = if is_sgb { sgb_buffer.lock().unwrap() } else { game_win_buffer.lock().unwrap() };
                         let mut gw_pixels = game_win.frame.frame_mut();
                         gw_pixels.copy_from_slice(&gw_buffer_unlocked);
                     }
@@ -337,6 +559,9 @@ fn main() {
             // tile_win.window.request_redraw();
             // bg_map_win.window.request_redraw();
         }).expect("Unable to run event loop in GBWindow");
+        // let the emu thread write the save ram and finish any recordings before the process goes away
+        let _ = emu_command_tx.send(EmuCommand::Shutdown);
+        let _ = emu_thread.join();
     } else {
         loop {
             emu.tick_no_window();
@@ -0,0 +1,32 @@
+// blargg's test roms, the failure message is whatever the rom printed
+// cargo test --test blargg
+
+use gbemu::gb::blargg::*;
+use gbemu::gb::mbc::{Mbc, OpSource, SERIAL_OUT_LIMIT};
+
+fn check(rom: &str) {
+    let result = run_blargg_rom(rom, DEFAULT_TIMEOUT_MCYCLES);
+    assert!(result.is_pass(), "{}: {}", rom, result.describe());
+}
+
+#[test]
+fn cpu_instrs() {
+    check("cpu_instrs.gb");
+}
+
+#[test]
+fn op_r_r() {
+    check("09-op-r-r.gb");
+}
+
+#[test]
+fn serial_out_is_capped() {
+    let mut mbc = Mbc::new();
+    for i in 0..SERIAL_OUT_LIMIT * 3 {
+        mbc.write(0xFF01, i as u8, OpSource::CPU);
+        mbc.write(0xFF02, 0x81, OpSource::CPU);
+    }
+    assert!(mbc.serial_out.len() <= SERIAL_OUT_LIMIT);
+    // the newest bytes are the ones kept
+    assert_eq!(mbc.serial_out.last(), Some(&((SERIAL_OUT_LIMIT * 3 - 1) as u8)));
+}
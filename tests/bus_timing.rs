@@ -0,0 +1,63 @@
+// the rest of the system steps on every cpu bus access, not once per instruction
+// cargo test --test bus_timing
+
+use gbemu::gb::bus::{Bus, BusCycle};
+use gbemu::gb::cpu::Cpu;
+use gbemu::gb::mbc::{Mbc, OpSource};
+
+// a bare mbc with the program at C000, nothing else runs
+fn setup(program: &[u8]) -> (Cpu, Mbc) {
+    let mut mbc = Mbc::new();
+    for (i, &byte) in program.iter().enumerate() {
+        mbc.write(0xC000 + i as u16, byte, OpSource::Debugger);
+    }
+    let mut cpu = Cpu::new();
+    cpu.rom_loaded = true;
+    cpu.registers.set_pc(0xC000);
+    cpu.registers.set_sp(0xD000);
+    (cpu, mbc)
+}
+
+#[test]
+fn call_pushes_one_byte_per_mcycle() {
+    // CALL D123
+    let (mut cpu, mut mbc) = setup(&[0xCD, 0x23, 0xD1]);
+    mbc.write(0xCFFF, 0x00, OpSource::Debugger);
+    mbc.write(0xCFFE, 0x00, OpSource::Debugger);
+    // what the rest of the system sees on the stack at the start of each mcycle
+    let mut stack = Vec::new();
+    let mut on_mcycle = |mbc: &mut Mbc| stack.push((mbc.read(0xCFFF, OpSource::Debugger), mbc.read(0xCFFE, OpSource::Debugger)));
+    let mut bus = Bus::new(&mut mbc, &mut on_mcycle);
+    bus.activity = Some(Vec::new());
+    assert_eq!(cpu.step(&mut bus), 6);
+    assert_eq!(bus.activity.take().unwrap(), [
+        BusCycle::Read(0xC000, 0xCD),
+        BusCycle::Read(0xC001, 0x23),
+        BusCycle::Read(0xC002, 0xD1),
+        BusCycle::Internal,
+        BusCycle::Write(0xCFFF, 0xC0),
+        BusCycle::Write(0xCFFE, 0x03),
+    ]);
+    // each write lands after its own mcycle was stepped, so the high byte shows up one mcycle before the low one
+    assert_eq!(stack, [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0xC0, 0)]);
+    assert_eq!(mbc.read(0xCFFE, OpSource::Debugger), 0x03);
+    assert_eq!(cpu.registers.get_pc(), 0xD123);
+}
+
+#[test]
+fn oam_dma_blocks_oam_for_160_mcycles() {
+    // LD A,C1 / LDH (46),A
+    let (mut cpu, mut mbc) = setup(&[0x3E, 0xC1, 0xE0, 0x46]);
+    mbc.write(0xFE00, 0x12, OpSource::Debugger);
+    mbc.write(0xC100, 0x34, OpSource::Debugger);
+    let mut no_op = |_: &mut Mbc| {};
+    let mut bus = Bus::new(&mut mbc, &mut no_op);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    // one mcycle of setup, then every read while a byte is copied sees FF, the last one included
+    let reads: Vec<u8> = (0..163).map(|_| bus.read(0xFE00, OpSource::CPU)).collect();
+    assert_eq!(reads[0], 0x12);
+    assert!(reads[1..161].iter().all(|&byte| byte == 0xFF));
+    assert_eq!(reads[161], 0x34);
+    assert!(!bus.dma_active);
+}
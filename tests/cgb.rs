@@ -0,0 +1,132 @@
+// game boy color mode, picked from the cart header
+// cargo test --test cgb
+
+use gbemu::gb::bios::ColorMode;
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+
+mod common;
+use common::*;
+
+#[test]
+fn header_picks_the_model() {
+    assert!(Headless::new(CGB_ROM).emu.mbc.is_cgb);
+    assert!(!Headless::new(DMG_ROM).emu.mbc.is_cgb);
+    assert!(!Headless::with_color_mode(CGB_ROM, Some(ColorMode::Gray)).emu.mbc.is_cgb);
+}
+
+#[test]
+fn cgb_boot() {
+    let mut headless = Headless::new(CGB_ROM);
+    run_boot_rom(&mut headless);
+    // a = 0x11 is how games know they're on a cgb
+    assert_eq!(headless.emu.cpu.registers.get_a(), 0x11);
+    assert!(headless.emu.mbc.is_cgb_mode());
+    assert_eq!(read(&headless, 0xFF4F), 0xFE);
+}
+
+#[test]
+fn dmg_cart_runs_in_compatibility_mode() {
+    let mut headless = Headless::with_color_mode(DMG_ROM, Some(ColorMode::Color));
+    run_boot_rom(&mut headless);
+    assert_eq!(headless.emu.mbc.hw_reg.key0 & 0x04, 0x04);
+    assert!(!headless.emu.mbc.is_cgb_mode());
+    // the banks are fixed and their registers are gone
+    write(&mut headless, 0xFF4F, 0x01);
+    write(&mut headless, 0xFF70, 0x03);
+    assert_eq!(read(&headless, 0xFF4F), 0xFF);
+    assert_eq!(read(&headless, 0xFF70), 0xFF);
+    assert_eq!(headless.emu.mbc.get_vram_bank(), 0);
+    assert_eq!(headless.emu.mbc.get_wram_bank(), 1);
+    // FF72 stays
+    write(&mut headless, 0xFF72, 0x5A);
+    assert_eq!(read(&headless, 0xFF72), 0x5A);
+    assert!(headless.run_frames(300));
+    assert!(headless.emu.cpu.locked_up.is_none());
+}
+
+#[test]
+fn vram_banks() {
+    // nothing has run yet, so the ppu isn't locking vram
+    let mut headless = Headless::new(CGB_ROM);
+    write(&mut headless, 0xFF4F, 0x01);
+    write(&mut headless, 0x8000, 0xAA);
+    write(&mut headless, 0x9FFF, 0xBB);
+    assert_eq!(read(&headless, 0xFF4F), 0xFF);
+    write(&mut headless, 0xFF4F, 0x00);
+    write(&mut headless, 0x8000, 0x55);
+    assert_eq!(read(&headless, 0x8000), 0x55);
+    assert_eq!(read(&headless, 0x9FFF), 0x00);
+    assert_eq!(headless.emu.mbc.read_vram(1, 0x8000), 0xAA);
+    assert_eq!(headless.emu.mbc.read_vram(1, 0x9FFF), 0xBB);
+    // the ppu doesn't go through VBK
+    write(&mut headless, 0xFF4F, 0x01);
+    assert_eq!(read(&headless, 0x8000), 0xAA);
+    assert_eq!(headless.emu.mbc.read(0x8000, OpSource::PPU), 0x55);
+}
+
+#[test]
+fn wram_banks() {
+    let mut headless = Headless::new(CGB_ROM);
+    write(&mut headless, 0xC000, 0x77);
+    for bank in 1..8u8 {
+        write(&mut headless, 0xFF70, bank);
+        write(&mut headless, 0xD000, bank);
+        write(&mut headless, 0xDFFF, bank << 4);
+    }
+    for bank in 1..8u8 {
+        write(&mut headless, 0xFF70, bank);
+        assert_eq!(read(&headless, 0xFF70), 0xF8 | bank);
+        assert_eq!(read(&headless, 0xD000), bank);
+        assert_eq!(read(&headless, 0xDFFF), bank << 4);
+        assert_eq!(read(&headless, 0xC000), 0x77);
+    }
+    // 0 is bank 1 but still reads back as 0
+    write(&mut headless, 0xFF70, 0x00);
+    assert_eq!(read(&headless, 0xFF70), 0xF8);
+    assert_eq!(read(&headless, 0xD000), 1);
+}
+
+#[test]
+fn register_masks() {
+    let mut cgb = Headless::new(CGB_ROM);
+    assert_eq!(read(&cgb, 0xFF4D), 0x7E);
+    write(&mut cgb, 0xFF4D, 0xFF);
+    assert_eq!(read(&cgb, 0xFF4D), 0x7F);
+    assert_eq!(read(&cgb, 0xFF56), 0x3E);
+    write(&mut cgb, 0xFF56, 0xFF);
+    assert_eq!(read(&cgb, 0xFF56), 0xFF);
+    assert_eq!(read(&cgb, 0xFF6C), 0xFE);
+    write(&mut cgb, 0xFF74, 0x12);
+    assert_eq!(read(&cgb, 0xFF74), 0x12);
+    assert_eq!(read(&cgb, 0xFF75), 0x8F);
+    write(&mut cgb, 0xFF75, 0xFF);
+    assert_eq!(read(&cgb, 0xFF75), 0xFF);
+    assert_eq!(read(&cgb, 0xFF76), 0x00);
+
+    // a dmg has none of them
+    let mut dmg = Headless::new(DMG_ROM);
+    for address in [0xFF4C, 0xFF4D, 0xFF4F, 0xFF56, 0xFF6C, 0xFF70, 0xFF72, 0xFF73, 0xFF74, 0xFF75, 0xFF76, 0xFF77] {
+        write(&mut dmg, address, 0x00);
+        assert_eq!(read(&dmg, address), 0xFF, "{:04X}", address);
+    }
+}
+
+#[test]
+fn double_speed() {
+    let mut headless = Headless::new(CGB_ROM);
+    run_boot_rom(&mut headless);
+    // ld a,1 / ldh (4D),a / stop / jr -2
+    for (i, byte) in [0x3E, 0x01, 0xE0, 0x4D, 0x10, 0x00, 0x18, 0xFE].into_iter().enumerate() {
+        write(&mut headless, 0xC000 + i as u16, byte);
+    }
+    headless.emu.cpu.registers.set_pc(0xC000);
+    assert!(headless.run_until(100, |emu| emu.mbc.hw_reg.is_double_speed()));
+    assert_eq!(read(&headless, 0xFF4D), 0xFE);
+
+    // the ppu keeps its pace, so a frame takes twice the cpu mcycles
+    assert!(headless.run_frames(1));
+    let start = headless.mcycles();
+    assert!(headless.run_frames(2));
+    assert_eq!(headless.mcycles() - start, 2 * 2 * 17_556);
+}
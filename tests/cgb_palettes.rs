@@ -0,0 +1,263 @@
+// cgb palette ram, bg map attributes and the sprite flags
+// cargo test --test cgb_palettes
+
+use gbemu::gb::bios::ColorMode;
+use gbemu::gb::graphics::fetcher::Fetcher;
+use gbemu::gb::graphics::fifo::Fifo;
+use gbemu::gb::graphics::palette::{get_cgb_rgba_code, PaletteColor};
+use gbemu::gb::graphics::pixel::GBPixel;
+use gbemu::gb::graphics::ppu::Ppu;
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+
+mod common;
+use common::*;
+
+const MAGENTA: [u8; 4] = [255, 0, 255, 255];
+
+fn get_pixel(buffer: &[u8], x: usize) -> [u8; 4] {
+    buffer[x * 4..x * 4 + 4].try_into().unwrap()
+}
+
+#[test]
+fn rgb555() {
+    let mut palette_ram = [0u8; 64];
+    palette_ram[0..2].copy_from_slice(&0x7FFFu16.to_le_bytes());
+    palette_ram[8 + 6..8 + 8].copy_from_slice(&0x001Fu16.to_le_bytes());
+    palette_ram[56 + 2..56 + 4].copy_from_slice(&(0x10u16 << 5 | 0x01 << 10).to_le_bytes());
+    assert_eq!(get_cgb_rgba_code(&palette_ram, 0, 0), WHITE);
+    assert_eq!(get_cgb_rgba_code(&palette_ram, 1, 3), RED);
+    assert_eq!(get_cgb_rgba_code(&palette_ram, 7, 1), [0, 0x84, 0x08, 255]);
+    assert_eq!(get_cgb_rgba_code(&palette_ram, 7, 0), [0, 0, 0, 255]);
+}
+
+#[test]
+fn palette_registers() {
+    let mut headless = Headless::new(CGB_ROM);
+    // auto increment
+    write(&mut headless, 0xFF68, 0x80 | 0x3E);
+    for byte in [0x11, 0x22, 0x33] {
+        write(&mut headless, 0xFF69, byte);
+    }
+    assert_eq!(read(&headless, 0xFF68), 0xC1);
+    assert_eq!(headless.emu.mbc.hw_reg.bg_palette_ram[0x3E..], [0x11, 0x22]);
+    assert_eq!(headless.emu.mbc.hw_reg.bg_palette_ram[0], 0x33);
+    // reads don't move the index, and without bit 7 neither do writes
+    write(&mut headless, 0xFF68, 0x3F);
+    assert_eq!(read(&headless, 0xFF68), 0x7F);
+    assert_eq!(read(&headless, 0xFF69), 0x22);
+    write(&mut headless, 0xFF69, 0x44);
+    assert_eq!(read(&headless, 0xFF69), 0x44);
+    assert_eq!(read(&headless, 0xFF68), 0x7F);
+
+    // the obj side is its own
+    write(&mut headless, 0xFF6A, 0x85);
+    write(&mut headless, 0xFF6B, 0x55);
+    assert_eq!(read(&headless, 0xFF6A), 0xC6);
+    assert_eq!(headless.emu.mbc.hw_reg.obj_palette_ram[5], 0x55);
+    assert_eq!(headless.emu.mbc.hw_reg.bg_palette_ram[5], 0x00);
+
+    // while the ppu draws the cpu can't get at the data but the index still moves
+    headless.emu.mbc.restrict_vram_access = true;
+    headless.emu.mbc.write(0xFF6B, 0x66, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xFF6B, OpSource::CPU), 0xFF);
+    assert_eq!(headless.emu.mbc.hw_reg.obj_palette_ram[6], 0x00);
+    assert_eq!(read(&headless, 0xFF6A), 0xC7);
+
+    // a dmg has no palette ram
+    let mut dmg = Headless::new(DMG_ROM);
+    for address in 0xFF68..=0xFF6B {
+        write(&mut dmg, address, 0x00);
+        assert_eq!(read(&dmg, address), 0xFF, "{:04X}", address);
+    }
+}
+
+// line 0 of the screen, after a couple of frames with the bg set up by hand
+fn draw_line(headless: &mut Headless) -> Vec<u8> {
+    assert!(headless.run_frames(2));
+    headless.copy_game_buffer()[..160 * 4].to_vec()
+}
+
+#[test]
+fn bg_attributes() {
+    let mut headless = Headless::new(CGB_ROM);
+    run_boot_rom(&mut headless);
+    // di / jr -2, nothing else touches the screen
+    for (i, byte) in [0xF3, 0x18, 0xFE].into_iter().enumerate() {
+        write(&mut headless, 0xC000 + i as u16, byte);
+    }
+    headless.emu.cpu.registers.set_pc(0xC000);
+    assert!(headless.run_until(100, |emu| emu.cpu.registers.get_pc() == 0xC001));
+
+    // bank 0 tile 0: row 0 is color 1 on the left half, everything else color 0
+    // bank 1 tile 0: all color 3
+    for bank in 0..2 {
+        write(&mut headless, 0xFF4F, bank);
+        for address in 0x8000..0xA000u16 {
+            write(&mut headless, address, 0x00);
+        }
+    }
+    write(&mut headless, 0xFF4F, 0);
+    write(&mut headless, 0x8000, 0xF0);
+    write(&mut headless, 0xFF4F, 1);
+    for address in 0x8000..0x8010u16 {
+        write(&mut headless, address, 0xFF);
+    }
+    // the attributes of the first 5 map tiles: plain, palette 1, x flip, y flip, bank 1
+    for (i, attributes) in [0x00, 0x01, 0x20, 0x40, 0x08].into_iter().enumerate() {
+        write(&mut headless, 0x9800 + i as u16, attributes);
+    }
+    write(&mut headless, 0xFF4F, 0);
+
+    // palette 0 is red, green, -, blue and palette 1 white, magenta
+    write(&mut headless, 0xFF68, 0x80);
+    for color in [0x001Fu16, 0x03E0, 0x0000, 0x7C00, 0x7FFF, 0x7C1F] {
+        for byte in color.to_le_bytes() {
+            write(&mut headless, 0xFF69, byte);
+        }
+    }
+    write(&mut headless, 0xFF42, 0);
+    write(&mut headless, 0xFF43, 0);
+    write(&mut headless, 0xFF40, 0x91);
+
+    let line = draw_line(&mut headless);
+    let expected = [
+        [GREEN, RED],
+        [MAGENTA, WHITE],
+        [RED, GREEN],
+        [RED, RED],
+        [BLUE, BLUE],
+    ];
+    for (tile, [left, right]) in expected.into_iter().enumerate() {
+        assert_eq!(get_pixel(&line, tile * 8), left, "tile {} left", tile);
+        assert_eq!(get_pixel(&line, tile * 8 + 7), right, "tile {} right", tile);
+    }
+
+    // scrolled down 7 rows, the y flipped tile shows its row 0
+    write(&mut headless, 0xFF42, 7);
+    let line = draw_line(&mut headless);
+    assert_eq!(get_pixel(&line, 0), RED);
+    assert_eq!(get_pixel(&line, 3 * 8), GREEN);
+    assert_eq!(get_pixel(&line, 3 * 8 + 7), RED);
+}
+
+#[test]
+fn dmg_cart_gets_colored() {
+    let mut headless = Headless::with_color_mode(DMG_ROM, Some(ColorMode::Color));
+    assert!(headless.run_frames(300));
+    assert!(!headless.emu.mbc.is_cgb_mode());
+    // the boot rom picked white, yellow, red, black for tetris
+    assert_eq!(headless.emu.mbc.hw_reg.bg_palette_ram[..8], [0xFF, 0x7F, 0xFF, 0x03, 0x1F, 0x00, 0x00, 0x00]);
+    // BGP still picks the shade, palette 0 colors it
+    write(&mut headless, 0xFF47, 0b0101_0101);
+    let line = draw_line(&mut headless);
+    assert_eq!(get_pixel(&line, 0), [255, 255, 0, 255]);
+    write(&mut headless, 0xFF47, 0b1010_1010);
+    let line = draw_line(&mut headless);
+    assert_eq!(get_pixel(&line, 0), RED);
+}
+
+// the low and high byte the fetcher gets for a sprite with these OAM Y and flags on line ly
+fn fetch_sprite_row(headless: &mut Headless, ly: u8, oam_y: u8, tile_num: usize, flags: u8) -> (u8, u8) {
+    headless.emu.mbc.hw_reg.ly = ly;
+    let mut fetcher = Fetcher::new();
+    fetcher.current_sprite_y = oam_y;
+    fetcher.current_attributes = flags;
+    fetcher.tcycle_budget = 4;
+    let low = fetcher.sprite_step_2_fetch_tile_data_low(&headless.emu.mbc, tile_num).ok().unwrap();
+    let high = fetcher.sprite_step_3_fetch_tile_data_high(&headless.emu.mbc, tile_num).ok().unwrap();
+    (low, high)
+}
+
+#[test]
+fn sprite_rows_and_banks() {
+    let mut headless = Headless::new(CGB_ROM);
+    // tiles 2 and 3 hold their own offset in bank 0, the same with bit 7 set in bank 1
+    for bank in 0..2 {
+        write(&mut headless, 0xFF4F, bank);
+        for i in 0..0x20u16 {
+            write(&mut headless, 0x8020 + i, (bank << 7) | i as u8);
+        }
+    }
+    write(&mut headless, 0xFF4F, 0);
+    write(&mut headless, 0xFF40, 0x91);
+
+    // OAM Y 20 on line 6 is row 2 of the sprite, whatever SCY is
+    write(&mut headless, 0xFF42, 5);
+    assert_eq!(fetch_sprite_row(&mut headless, 6, 20, 2, 0x00), (0x04, 0x05));
+    // flipped it's row 5
+    assert_eq!(fetch_sprite_row(&mut headless, 6, 20, 2, 0x40), (0x0A, 0x0B));
+    // bit 3 reads the tile out of bank 1
+    assert_eq!(fetch_sprite_row(&mut headless, 6, 20, 2, 0x08), (0x84, 0x85));
+
+    // 8x16, the tile number's low bit is ignored and row 10 is in the bottom tile
+    write(&mut headless, 0xFF40, 0x95);
+    assert_eq!(fetch_sprite_row(&mut headless, 14, 20, 3, 0x00), (0x14, 0x15));
+    // flipped the whole 16 rows turn over, row 10 shows row 5
+    assert_eq!(fetch_sprite_row(&mut headless, 14, 20, 3, 0x40), (0x0A, 0x0B));
+    assert_eq!(fetch_sprite_row(&mut headless, 4, 20, 2, 0x40), (0x1E, 0x1F));
+}
+
+// the pixels the fetcher pushes for a row of color 1 with these flags
+fn push_sprite_row(headless: &Headless, flags: u8) -> Vec<GBPixel> {
+    let mut fetcher = Fetcher::new();
+    fetcher.current_attributes = flags;
+    fetcher.tcycle_budget = 2;
+    let mut fifo = Fifo::new();
+    assert!(fetcher.sprite_step_4_push_pixels_to_fifo(&headless.emu.mbc, 0, 0xFF, 0x00, false, &mut fifo).is_ok());
+    fifo.data.into_iter().collect()
+}
+
+#[test]
+fn sprite_palettes() {
+    let mut headless = Headless::new(CGB_ROM);
+    // obj palette 5 color 1 is red, bg palette 5 stays black
+    write(&mut headless, 0xFF6A, 0x80 | (5 * 8 + 2));
+    for byte in 0x001Fu16.to_le_bytes() {
+        write(&mut headless, 0xFF6B, byte);
+    }
+    let pixels = push_sprite_row(&headless, 0x05);
+    assert_eq!(pixels.len(), 8);
+    assert!(pixels.iter().all(|px| px.palette == 5 && px.color == PaletteColor::LightGray));
+    assert_eq!(Ppu::get_pixel_rgba_code(&headless.emu.mbc, &pixels[0], true), RED);
+    assert_eq!(Ppu::get_pixel_rgba_code(&headless.emu.mbc, &pixels[0], false), [0, 0, 0, 255]);
+
+    // a dmg sprite picks OBP0 or OBP1 with bit 4, the cgb bits mean nothing
+    let dmg = Headless::new(DMG_ROM);
+    assert!(push_sprite_row(&dmg, 0x15).iter().all(|px| px.palette == 1));
+    assert!(push_sprite_row(&dmg, 0x05).iter().all(|px| px.palette == 0));
+}
+
+fn pixel(color: PaletteColor, bg_priority: bool) -> GBPixel {
+    GBPixel {
+        color,
+        bg_priority,
+        skip: false,
+        palette: 0,
+    }
+}
+
+#[test]
+fn sprite_priority() {
+    use PaletteColor::*;
+    let mut headless = Headless::new(CGB_ROM);
+    write(&mut headless, 0xFF40, 0x91);
+    let is_drawn = |headless: &Headless, bg: GBPixel, sprite: GBPixel| Ppu::is_sprite_pixel_drawn(&headless.emu.mbc, &bg, &sprite);
+
+    // color 0 of a sprite never shows, color 0 of the bg never hides one
+    assert!(!is_drawn(&headless, pixel(White, false), pixel(White, false)));
+    assert!(is_drawn(&headless, pixel(White, true), pixel(Black, true)));
+    // either priority bit puts bg colors 1-3 on top
+    assert!(is_drawn(&headless, pixel(DarkGray, false), pixel(Black, false)));
+    assert!(!is_drawn(&headless, pixel(DarkGray, true), pixel(Black, false)));
+    assert!(!is_drawn(&headless, pixel(DarkGray, false), pixel(Black, true)));
+    // LCDC bit 0 off and sprites go over everything
+    write(&mut headless, 0xFF40, 0x90);
+    assert!(is_drawn(&headless, pixel(DarkGray, true), pixel(Black, true)));
+
+    // a dmg bg has no priority bit, only the sprite's counts
+    let mut dmg = Headless::new(DMG_ROM);
+    write(&mut dmg, 0xFF40, 0x91);
+    assert!(is_drawn(&dmg, pixel(DarkGray, true), pixel(Black, false)));
+    assert!(!is_drawn(&dmg, pixel(DarkGray, false), pixel(Black, true)));
+}
@@ -0,0 +1,29 @@
+// helpers shared by the cgb, cgb_palettes, hdma and sgb tests, pulled in with `mod common;`
+// every test file uses a different part of this
+#![allow(dead_code)]
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+
+pub const CGB_ROM: &str = "cpu_instrs.gb"; // 0x80, runs on both
+pub const DMG_ROM: &str = "tetris.gb";
+
+// mcycles the boot rom gets to hand over, it's done well before this on both models
+pub const BOOT_ROM_BUDGET: u64 = 10 * 1_048_576;
+
+pub const RED: [u8; 4] = [255, 0, 0, 255];
+pub const GREEN: [u8; 4] = [0, 255, 0, 255];
+pub const BLUE: [u8; 4] = [0, 0, 255, 255];
+pub const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+pub fn read(headless: &Headless, address: u16) -> u8 {
+    headless.emu.mbc.read(address, OpSource::Debugger)
+}
+
+pub fn write(headless: &mut Headless, address: u16, byte: u8) {
+    headless.emu.mbc.write(address, byte, OpSource::Debugger);
+}
+
+pub fn run_boot_rom(headless: &mut Headless) {
+    assert!(headless.run_until(BOOT_ROM_BUDGET, |emu| emu.mbc.hw_reg.boot_rom_control != 0), "boot rom didn't finish");
+}
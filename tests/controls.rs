@@ -0,0 +1,79 @@
+// pause, frame advance and resets
+// cargo test --test controls
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+use gbemu::gb::pacer::Speed;
+
+const ROM: &str = "tetris.gb";
+
+fn get_state(headless: &Headless) -> (u64, u16, u16, Vec<u8>) {
+    let emu = &headless.emu;
+    let ram = (0xC000..0xE000u16).map(|address| emu.mbc.read(address, OpSource::Debugger)).collect();
+    (emu.cpu.total_mcycles, emu.cpu.registers.get_pc(), emu.cpu.registers.get_af(), ram)
+}
+
+// tick is what the window thread runs, it's the one that knows about pausing
+fn tick(headless: &mut Headless) {
+    headless.emu.tick(&headless.tile_buffer, &headless.bg_map_buffer, &headless.game_buffer);
+}
+
+#[test]
+fn hard_reset_is_a_power_cycle() {
+    let mut fresh = Headless::new(ROM);
+    assert!(fresh.run_frames(400));
+    let expected = get_state(&fresh);
+
+    let mut reset = Headless::new(ROM);
+    assert!(reset.run_frames(450));
+    reset.emu.hard_reset();
+    assert_eq!(reset.emu.cpu.total_mcycles, 0);
+    assert_eq!(reset.emu.cpu.registers.get_pc(), 0);
+    assert_eq!(reset.emu.mbc.hw_reg.boot_rom_control, 0);
+    reset.frames = 0;
+    assert!(reset.run_frames(400));
+    assert!(get_state(&reset) == expected, "a hard reset ran differently from power-on");
+}
+
+#[test]
+fn soft_reset_keeps_ram() {
+    let mut headless = Headless::new(ROM);
+    assert!(headless.run_frames(400));
+    headless.emu.mbc.write(0xC123, 0x42, OpSource::Debugger);
+    headless.emu.mbc.write(0xFF90, 0x24, OpSource::Debugger);
+
+    headless.emu.soft_reset();
+    assert_eq!(headless.emu.cpu.registers.get_pc(), 0);
+    assert_eq!(headless.emu.mbc.read(0xC123, OpSource::Debugger), 0x42);
+    assert_eq!(headless.emu.mbc.read(0xFF90, OpSource::Debugger), 0x24);
+    // and it still boots into the game
+    assert!(headless.run_until(10 * 1_048_576, |emu| emu.mbc.hw_reg.boot_rom_control != 0));
+
+    headless.emu.mbc.write(0xC123, 0x42, OpSource::Debugger);
+    headless.emu.hard_reset();
+    assert_ne!(headless.emu.mbc.read(0xC123, OpSource::Debugger), 0x42);
+}
+
+#[test]
+fn frame_advance() {
+    let mut headless = Headless::new(ROM);
+    headless.emu.pacer.set_speed(Speed::Unthrottled);
+    headless.emu.set_paused(true);
+    for _ in 0..10 {
+        tick(&mut headless);
+    }
+    assert_eq!(headless.emu.cpu.total_mcycles, 0, "ran while paused");
+
+    // every frame is 17556 mcycles while the lcd is off, the boot rom turns it on later
+    headless.emu.advance_frames(3);
+    while headless.emu.is_running() {
+        tick(&mut headless);
+    }
+    let mcycles = headless.emu.cpu.total_mcycles;
+    assert!((3 * 17_556..3 * 17_556 + 8).contains(&mcycles), "advanced to mcycle {}", mcycles);
+    assert!(headless.emu.is_paused);
+
+    headless.emu.set_paused(false);
+    tick(&mut headless);
+    assert!(headless.emu.cpu.total_mcycles > mcycles);
+}
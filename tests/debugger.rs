@@ -0,0 +1,102 @@
+// debugger console commands and breakpoint conditions
+// cargo test --test debugger
+
+use std::sync::Arc;
+
+use gbemu::gb::cpu::Cpu;
+use gbemu::gb::debugger::*;
+use gbemu::gb::emu::EmuCommand;
+use gbemu::gb::mbc::Mbc;
+use gbemu::gb::symbols::Symbols;
+
+fn condition(register: &str, op: CompareOp, value: u16) -> Option<Condition> {
+    Some(Condition { register: String::from(register), op, value })
+}
+
+#[test]
+fn conditions() {
+    assert_eq!(parse_condition(&["a", "==", "3"]), condition("a", CompareOp::Eq, 3));
+    assert_eq!(parse_condition(&["HL", "!=", "c000"]), condition("hl", CompareOp::Ne, 0xC000));
+    assert_eq!(parse_condition(&["sp", "<=", "$dff0"]), condition("sp", CompareOp::Le, 0xDFF0));
+    assert_eq!(parse_condition(&["pc", ">", "0x150"]), condition("pc", CompareOp::Gt, 0x150));
+    // not a register, not an op, not hex, not three words
+    assert_eq!(parse_condition(&["ix", "==", "3"]), None);
+    assert_eq!(parse_condition(&["a", "=", "3"]), None);
+    assert_eq!(parse_condition(&["a", "==", "xyz"]), None);
+    assert_eq!(parse_condition(&["a", "=="]), None);
+
+    let mut cpu = Cpu::new();
+    cpu.registers.set_hl(0xC000);
+    assert!(!condition("hl", CompareOp::Ne, 0xC000).unwrap().is_met(&cpu));
+    assert!(condition("hl", CompareOp::Ge, 0xBFFF).unwrap().is_met(&cpu));
+}
+
+#[test]
+fn breakpoints() {
+    let cpu = Cpu::new();
+    let mut mbc = Mbc::new();
+    let mut debugger = Debugger::default();
+    let mut symbols = Symbols::new();
+    symbols.insert(2, 0x4100, "cafe");
+    debugger.symbols = Some(Arc::new(symbols));
+
+    debugger.run_command("break 150", &cpu, &mut mbc);
+    debugger.run_command("b 3:4000 if a == ff", &cpu, &mut mbc);
+    debugger.run_command("b if bc < 10", &cpu, &mut mbc);
+    // labels win over hex
+    debugger.run_command("b cafe", &cpu, &mut mbc);
+    assert_eq!(debugger.breakpoints, [
+        Breakpoint { bank: None, address: Some(0x150), condition: None },
+        Breakpoint { bank: Some(3), address: Some(0x4000), condition: condition("a", CompareOp::Eq, 0xFF) },
+        Breakpoint { bank: None, address: None, condition: condition("bc", CompareOp::Lt, 0x10) },
+        Breakpoint { bank: Some(2), address: Some(0x4100), condition: None },
+    ]);
+
+    // bad ones don't add anything
+    for command in ["b", "b nowhere", "b 150 a == 3", "b 150 if q == 3", "b if"] {
+        debugger.run_command(command, &cpu, &mut mbc);
+    }
+    assert_eq!(debugger.breakpoints.len(), 4);
+
+    debugger.run_command("del 1", &cpu, &mut mbc);
+    debugger.run_command("del 9", &cpu, &mut mbc);
+    assert_eq!(debugger.breakpoints.len(), 3);
+    assert_eq!(debugger.breakpoints[1].address, None);
+}
+
+#[test]
+fn watchpoints() {
+    let cpu = Cpu::new();
+    let mut mbc = Mbc::new();
+    let mut debugger = Debugger::new();
+    debugger.run_command("watch c100", &cpu, &mut mbc);
+    debugger.run_command("w ff80-ff40 rx", &cpu, &mut mbc);
+    debugger.run_command("w c000 q", &cpu, &mut mbc);
+    assert_eq!(mbc.watchpoints, [
+        Watchpoint { start: 0xC100, end: 0xC100, read: false, write: true, execute: false },
+        Watchpoint { start: 0xFF40, end: 0xFF80, read: true, write: false, execute: true },
+    ]);
+    debugger.run_command("clear", &cpu, &mut mbc);
+    assert!(mbc.watchpoints.is_empty());
+}
+
+#[test]
+fn emu_commands() {
+    let cpu = Cpu::new();
+    let mut mbc = Mbc::new();
+    let mut debugger = Debugger::new();
+    for command in ["reset", "reset hard", "reset sideways", "frame 3", "load other game.gb", "load"] {
+        debugger.run_command(command, &cpu, &mut mbc);
+    }
+    assert_eq!(debugger.emu_commands, [
+        EmuCommand::SoftReset,
+        EmuCommand::HardReset,
+        EmuCommand::AdvanceFrames(3),
+        EmuCommand::LoadRom(String::from("other game.gb")),
+    ]);
+
+    debugger.run_command("s", &cpu, &mut mbc);
+    assert_eq!(debugger.mode, DebugMode::StepInto);
+    debugger.run_command("out", &cpu, &mut mbc);
+    assert_eq!(debugger.mode, DebugMode::StepOut { sp: cpu.registers.get_sp() });
+}
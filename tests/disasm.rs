@@ -0,0 +1,82 @@
+// operand formatting in the disassembler
+// cargo test --test disasm
+
+use gbemu::gb::disasm::*;
+use gbemu::gb::symbols::Symbols;
+
+// the bytes sit at address, anything after them reads as 00
+fn dis(bytes: &[u8], address: u16) -> Disassembled {
+    let read = |addr: u16| bytes.get(addr.wrapping_sub(address) as usize).copied().unwrap_or(0x00);
+    disassemble(&read, address, 0)
+}
+
+// bytes, address, text, size, target
+type Case = (&'static [u8], u16, &'static str, u16, Option<u16>);
+
+#[test]
+fn operands() {
+    let table: &[Case] = &[
+        (&[0x00], 0x0100, "NOP", 1, None),
+        (&[0x3E, 0x42], 0x0100, "LD A,$42", 2, None),
+        (&[0x21, 0x00, 0xC0], 0x0100, "LD HL,$C000", 3, None),
+        (&[0xEA, 0x34, 0x12], 0x0100, "LD ($1234),A", 3, None),
+        (&[0xE0, 0x40], 0x0100, "LD (FF00+$40),A", 2, None),
+        (&[0xE2], 0x0100, "LD (FF00+C),A", 1, None),
+        (&[0xC3, 0x50, 0x01], 0x0100, "JP $0150", 3, Some(0x0150)),
+        (&[0xCD, 0xFD, 0x48], 0x0100, "CALL $48FD", 3, Some(0x48FD)),
+        (&[0xC4, 0x00, 0x40], 0x0100, "CALL NZ,$4000", 3, Some(0x4000)),
+        // jr offsets count from the jr itself
+        (&[0x18, 0xFE], 0x0200, "JR $+0", 2, Some(0x0200)),
+        (&[0x20, 0xFB], 0x0200, "JR NZ,$-3", 2, Some(0x01FD)),
+        (&[0x38, 0x10], 0x0200, "JR C,$+18", 2, Some(0x0212)),
+        (&[0xFF], 0x0100, "RST $38", 1, Some(0x0038)),
+        (&[0xE8, 0xFE], 0x0100, "ADD SP,-2", 2, None),
+        (&[0xF8, 0x05], 0x0100, "LD HL,SP+5", 2, None),
+        (&[0xF8, 0x80], 0x0100, "LD HL,SP-128", 2, None),
+        (&[0x10, 0x00], 0x0100, "STOP", 2, None),
+        // cb prefixed
+        (&[0xCB, 0x7C], 0x0100, "BIT 7,H", 2, None),
+        (&[0xCB, 0x37], 0x0100, "SWAP A", 2, None),
+        (&[0xCB, 0x86], 0x0100, "RES 0,(HL)", 2, None),
+        // illegal opcodes are data
+        (&[0xD3], 0x0100, "DB $D3", 1, None),
+        (&[0xFD], 0x0100, "DB $FD", 1, None),
+    ];
+    for &(bytes, address, text, size, target) in table {
+        let line = dis(bytes, address);
+        assert_eq!(line.text, text, "{:02X?}", bytes);
+        assert_eq!(line.size(), size, "{}", text);
+        assert_eq!(line.bytes, bytes[..size as usize], "{}", text);
+        assert_eq!(line.target, target, "{}", text);
+    }
+}
+
+#[test]
+fn range_and_labels() {
+    let program = [0x00, 0xCD, 0x00, 0x40, 0xCB, 0x11, 0xDD];
+    let read = |addr: u16| program.get(addr as usize - 0x150).copied().unwrap_or(0x00);
+    let lines = disassemble_range(&read, 0x150, 0, 4);
+    let addresses: Vec<u16> = lines.iter().map(|line| line.address).collect();
+    assert_eq!(addresses, [0x150, 0x151, 0x154, 0x156]);
+
+    let mut symbols = Symbols::new();
+    symbols.insert(0, 0x151, "main");
+    symbols.insert(2, 0x4000, "banked");
+    let mut line = lines[1].clone();
+    // the target is in whatever bank is mapped at 4000-7FFF
+    line.apply_symbols(&symbols, 1);
+    assert_eq!(line.text, "CALL $4000");
+    line.apply_symbols(&symbols, 2);
+    assert_eq!(line.text, "CALL banked");
+    assert_eq!(line.to_line(), "main:\n00:0151  CD 00 40   CALL banked");
+}
+
+#[test]
+fn bank_addresses() {
+    assert_eq!(parse_bank_address("01:4000"), Some((1, 0x4000)));
+    assert_eq!(parse_bank_address("$0150"), Some((0, 0x0150)));
+    assert_eq!(parse_bank_address("0x5000"), Some((1, 0x5000)));
+    assert_eq!(parse_bank_address("100:4000"), None);
+    assert_eq!(parse_bank_address("10000"), None);
+    assert_eq!(parse_bank_address("main"), None);
+}
@@ -0,0 +1,126 @@
+// the gdb stub over a real socket, the test plays the client
+// cargo test --test gdbstub
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use gbemu::gb::bus::Bus;
+use gbemu::gb::cpu::Cpu;
+use gbemu::gb::gdbstub::{GdbStub, GdbState};
+use gbemu::gb::mbc::{Mbc, OpSource};
+
+fn frame(packet: &str) -> String {
+    let checksum = packet.bytes().fold(0u8, |sum, b| sum.wrapping_add(b));
+    format!("${}#{:02x}", packet, checksum)
+}
+
+// the ack for the command and the reply to it
+fn reply(packet: &str) -> String {
+    format!("+{}", frame(packet))
+}
+
+struct Target {
+    gdb: GdbStub,
+    cpu: Cpu,
+    mbc: Mbc,
+    client: TcpStream,
+}
+
+impl Target {
+    // a bare mbc with the program at C000 and a client attached
+    fn new(program: &[u8]) -> Self {
+        let mut mbc = Mbc::new();
+        for (i, &byte) in program.iter().enumerate() {
+            mbc.write(0xC000 + i as u16, byte, OpSource::Debugger);
+        }
+        let mut cpu = Cpu::new();
+        cpu.rom_loaded = true;
+        cpu.registers.set_pc(0xC000);
+        let mut gdb = GdbStub::new(0).unwrap();
+        let client = TcpStream::connect(("127.0.0.1", gdb.get_port())).unwrap();
+        client.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+        for _ in 0..10_000 {
+            if gdb.state == GdbState::Stopped {
+                break;
+            }
+            gdb.before_step(&mut cpu, &mut mbc);
+        }
+        assert_eq!(gdb.state, GdbState::Stopped, "the stub never picked up the client");
+        Target { gdb, cpu, mbc, client }
+    }
+
+    // what the emu does every step
+    fn step(&mut self) {
+        if self.gdb.before_step(&mut self.cpu, &mut self.mbc) {
+            let mut no_op = |_: &mut Mbc| {};
+            let mut bus = Bus::new(&mut self.mbc, &mut no_op);
+            self.cpu.step(&mut bus);
+            self.gdb.after_step(&self.cpu, &mut self.mbc);
+        }
+    }
+
+    fn send_raw(&mut self, data: &str) {
+        self.client.write_all(data.as_bytes()).unwrap();
+    }
+
+    fn send(&mut self, packet: &str) {
+        self.send_raw(&frame(packet));
+    }
+
+    // steps until the stub has said something and returns all of it
+    fn receive(&mut self) -> String {
+        let mut text = String::new();
+        let mut chunk = [0u8; 1024];
+        for _ in 0..200 {
+            self.step();
+            if let Ok(n) = self.client.read(&mut chunk) {
+                text.push_str(&String::from_utf8_lossy(&chunk[..n]));
+            }
+            let is_packet_done = text.find('#').is_some_and(|idx| text.len() >= idx + 3);
+            if text == "-" || is_packet_done {
+                break;
+            }
+        }
+        text
+    }
+}
+
+#[test]
+fn registers() {
+    let mut target = Target::new(&[0x00]);
+    target.cpu.registers.set_af(0x01B0);
+    target.send("p0");
+    assert_eq!(target.receive(), reply("b001"));
+}
+
+#[test]
+fn bad_checksum_is_nak() {
+    let mut target = Target::new(&[0x00]);
+    target.send_raw("$p0#00");
+    assert_eq!(target.receive(), "-");
+    // the client sends it again, right this time
+    target.send("p5");
+    assert_eq!(target.receive(), reply("00c0"));
+}
+
+#[test]
+fn watchpoint_stop_reasons() {
+    // LD A,(D000) / LD (D000),A / LD A,(D000)
+    let mut target = Target::new(&[0xFA, 0x00, 0xD0, 0xEA, 0x00, 0xD0, 0xFA, 0x00, 0xD0]);
+    // an access watchpoint reports awatch for a read
+    target.send("Z4,d000,1");
+    assert_eq!(target.receive(), reply("OK"));
+    target.send("c");
+    assert_eq!(target.receive(), reply("T05awatch:d000;"));
+    assert_eq!(target.cpu.registers.get_pc(), 0xC003);
+
+    // swapped for a write watchpoint, the read after the write doesn't stop
+    target.send("z4,d000,1");
+    assert_eq!(target.receive(), reply("OK"));
+    target.send("Z2,d000,1");
+    assert_eq!(target.receive(), reply("OK"));
+    target.send("c");
+    assert_eq!(target.receive(), reply("T05watch:d000;"));
+    assert_eq!(target.cpu.registers.get_pc(), 0xC006);
+}
@@ -0,0 +1,116 @@
+// cgb general purpose and hblank dma through HDMA1-HDMA5
+// cargo test --test hdma
+
+use gbemu::gb::graphics::ppu::PPUMode;
+use gbemu::gb::headless::Headless;
+
+mod common;
+use common::*;
+
+// copies from D000 to 8100, HDMA5 is the last write and the nop after it is at C013
+fn start_transfer(hdma5: u8) -> Headless {
+    let mut headless = Headless::new(CGB_ROM);
+    run_boot_rom(&mut headless);
+    for i in 0..0x80u16 {
+        write(&mut headless, 0xD000 + i, i as u8 + 1);
+    }
+    // the boot rom leaves its logo in vram
+    headless.emu.mbc.restrict_vram_access = false;
+    for address in 0x8100..0x8200u16 {
+        write(&mut headless, address, 0x00);
+    }
+    let program = [
+        0xF3, // di
+        0x3E, 0xD0, 0xE0, 0x51, // ld a,D0 / ldh (51),a
+        0xAF, 0xE0, 0x52, // xor a / ldh (52),a
+        0x3E, 0x01, 0xE0, 0x53, // ld a,01 / ldh (53),a
+        0xAF, 0xE0, 0x54, // xor a / ldh (54),a
+        0x3E, hdma5, 0xE0, 0x55, // ld a,hdma5 / ldh (55),a
+        0x00, // nop
+        0x18, 0xFE, // jr -2
+    ];
+    for (i, byte) in program.into_iter().enumerate() {
+        write(&mut headless, 0xC000 + i as u16, byte);
+    }
+    headless.emu.cpu.registers.set_pc(0xC000);
+    headless
+}
+
+fn run_to(headless: &mut Headless, pc: u16) {
+    assert!(headless.run_until(100_000, |emu| emu.cpu.registers.get_pc() == pc), "never got to {:04X}", pc);
+}
+
+fn vram(headless: &Headless, address: u16) -> u8 {
+    headless.emu.mbc.read_vram(0, address)
+}
+
+// mcycles from the HDMA5 write to the end of the nop after it
+fn time_general_purpose(is_double_speed: bool) -> u64 {
+    let mut headless = start_transfer(0x03);
+    write(&mut headless, 0xFF40, 0x00);
+    if is_double_speed {
+        headless.emu.mbc.hw_reg.key1 = 0x80;
+    }
+    run_to(&mut headless, 0xC013);
+    let start = headless.mcycles();
+    run_to(&mut headless, 0xC014);
+    for i in 0..0x40u16 {
+        assert_eq!(vram(&headless, 0x8100 + i), i as u8 + 1);
+    }
+    assert_eq!(vram(&headless, 0x8140), 0x00);
+    assert_eq!(read(&headless, 0xFF55), 0xFF);
+    headless.mcycles() - start
+}
+
+#[test]
+fn general_purpose() {
+    // 4 blocks of 8 mcycles with the cpu halted, then the nop
+    assert_eq!(time_general_purpose(false), 4 * 8 + 1);
+    // same time, twice the cpu mcycles
+    assert_eq!(time_general_purpose(true), 4 * 16 + 1);
+}
+
+#[test]
+fn hblank() {
+    let mut headless = start_transfer(0x83);
+    run_to(&mut headless, 0xC013);
+    assert_eq!(read(&headless, 0xFF55), 0x03);
+    assert_eq!(vram(&headless, 0x8100), 0x00);
+
+    // one block per hblank
+    assert!(headless.run_until(20_000, |emu| emu.mbc.hw_reg.hdma5 == 0x02));
+    assert_eq!(headless.emu.ppu.mode, PPUMode::Mode_0_H_Blank);
+    assert_eq!(vram(&headless, 0x810F), 0x10);
+    assert_eq!(vram(&headless, 0x8110), 0x00);
+    let line = headless.emu.mbc.hw_reg.ly;
+    assert!(headless.run_until(20_000, |emu| emu.mbc.hw_reg.hdma5 == 0x01));
+    assert_eq!(headless.emu.mbc.hw_reg.ly, line + 1);
+    assert_eq!(vram(&headless, 0x811F), 0x20);
+
+    // stopped with 2 blocks to go
+    write(&mut headless, 0xFF55, 0x00);
+    assert_eq!(read(&headless, 0xFF55), 0x81);
+    assert!(headless.run_frames(2));
+    assert_eq!(read(&headless, 0xFF55), 0x81);
+    assert_eq!(vram(&headless, 0x8120), 0x00);
+
+    // and started again without touching HDMA1-4, it carries on from D020 to 8120
+    write(&mut headless, 0xFF55, 0x81);
+    assert!(headless.run_frames(1));
+    assert_eq!(read(&headless, 0xFF55), 0xFF);
+    assert_eq!(vram(&headless, 0x8100), 0x01);
+    for i in 0x20..0x40u16 {
+        assert_eq!(vram(&headless, 0x8100 + i), i as u8 + 1);
+    }
+    assert_eq!(vram(&headless, 0x8140), 0x00);
+}
+
+#[test]
+fn dmg_has_no_hdma() {
+    let mut headless = Headless::new(DMG_ROM);
+    for address in 0xFF51..=0xFF55 {
+        write(&mut headless, address, 0x00);
+        assert_eq!(read(&headless, address), 0xFF, "{:04X}", address);
+    }
+    assert_eq!(headless.emu.mbc.hdma_bytes_left, 0);
+}
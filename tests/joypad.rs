@@ -0,0 +1,99 @@
+// the JOYP button matrix and when it raises the joypad interrupt
+// cargo test --test joypad
+
+use gbemu::gb::joypad::Button;
+use gbemu::gb::mbc::{Mbc, OpSource};
+
+const SELECT_DPAD: u8 = 0x20;
+const SELECT_BUTTONS: u8 = 0x10;
+const SELECT_BOTH: u8 = 0x00;
+const SELECT_NONE: u8 = 0x30;
+
+fn read_joyp(mbc: &mut Mbc) -> u8 {
+    mbc.read(0xFF00, OpSource::CPU)
+}
+
+fn write_joyp(mbc: &mut Mbc, byte: u8) {
+    mbc.write(0xFF00, byte, OpSource::CPU);
+}
+
+fn press(mbc: &mut Mbc, buttons: &[Button]) {
+    let bits = buttons.iter().fold(0, |bits, button| bits | button.get_bit());
+    if mbc.joypad.set_buttons(bits) {
+        mbc.hw_reg.set_if_joypad_bit4();
+    }
+}
+
+#[test]
+fn a_and_right_are_separate() {
+    let mut mbc = Mbc::new();
+    press(&mut mbc, &[Button::A]);
+    write_joyp(&mut mbc, SELECT_DPAD);
+    assert_eq!(read_joyp(&mut mbc), 0xEF);
+    write_joyp(&mut mbc, SELECT_BUTTONS);
+    assert_eq!(read_joyp(&mut mbc), 0xDE);
+
+    press(&mut mbc, &[Button::Right, Button::Start]);
+    assert_eq!(read_joyp(&mut mbc), 0xD7);
+    write_joyp(&mut mbc, SELECT_DPAD);
+    assert_eq!(read_joyp(&mut mbc), 0xEE);
+}
+
+#[test]
+fn both_rows_and_none() {
+    let mut mbc = Mbc::new();
+    press(&mut mbc, &[Button::B, Button::Up]);
+    write_joyp(&mut mbc, SELECT_BOTH);
+    assert_eq!(read_joyp(&mut mbc), 0xC9);
+    write_joyp(&mut mbc, SELECT_NONE);
+    assert_eq!(read_joyp(&mut mbc), 0xFF);
+    // deselecting doesn't forget what's held
+    write_joyp(&mut mbc, SELECT_DPAD);
+    assert_eq!(read_joyp(&mut mbc), 0xEB);
+}
+
+#[test]
+fn release() {
+    let mut mbc = Mbc::new();
+    write_joyp(&mut mbc, SELECT_DPAD);
+    press(&mut mbc, &[Button::Down]);
+    assert_eq!(read_joyp(&mut mbc), 0xE7);
+    press(&mut mbc, &[]);
+    assert_eq!(read_joyp(&mut mbc), 0xEF);
+}
+
+#[test]
+fn interrupt_on_selected_falling_edge_only() {
+    let mut mbc = Mbc::new();
+    write_joyp(&mut mbc, SELECT_DPAD);
+
+    // a button on the other row doesn't touch the lines
+    press(&mut mbc, &[Button::A]);
+    assert!(!mbc.hw_reg.is_if_joypad_bit4_set());
+
+    press(&mut mbc, &[Button::A, Button::Left]);
+    assert!(mbc.hw_reg.is_if_joypad_bit4_set());
+    mbc.hw_reg.clear_if_joypad_bit4();
+
+    // held is not a new edge, and neither is a release
+    press(&mut mbc, &[Button::A, Button::Left]);
+    press(&mut mbc, &[Button::A]);
+    assert!(!mbc.hw_reg.is_if_joypad_bit4_set());
+
+    // right shares bit 0 with a, which is already low once both rows are selected
+    write_joyp(&mut mbc, SELECT_BOTH);
+    mbc.hw_reg.clear_if_joypad_bit4();
+    press(&mut mbc, &[Button::A, Button::Right]);
+    assert!(!mbc.hw_reg.is_if_joypad_bit4_set());
+}
+
+#[test]
+fn interrupt_on_selecting_a_held_row() {
+    let mut mbc = Mbc::new();
+    press(&mut mbc, &[Button::Start]);
+    assert!(!mbc.hw_reg.is_if_joypad_bit4_set());
+    write_joyp(&mut mbc, SELECT_DPAD);
+    assert!(!mbc.hw_reg.is_if_joypad_bit4_set());
+    write_joyp(&mut mbc, SELECT_BUTTONS);
+    assert!(mbc.hw_reg.is_if_joypad_bit4_set());
+}
@@ -0,0 +1,89 @@
+// key binding files
+// cargo test --test keybindings
+
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use gbemu::gb::joypad::Button;
+use gbemu::gb::keybindings::*;
+use winit::keyboard::KeyCode;
+
+const OUTPUT_DIR: &str = "target/keybindings";
+
+#[test]
+fn a_and_right_are_separate() {
+    let bindings = KeyBindings::new();
+    assert_eq!(bindings.get_action(KeyCode::KeyK), Some(KeyAction::Button(Button::A)));
+    assert_eq!(bindings.get_action(KeyCode::KeyD), Some(KeyAction::Button(Button::Right)));
+    assert_eq!(bindings.get_action(KeyCode::F5), Some(KeyAction::Hotkey(Hotkey::SaveState)));
+    assert_eq!(bindings.get_action(KeyCode::F8), Some(KeyAction::Hotkey(Hotkey::LoadState)));
+}
+
+#[test]
+fn file_overrides_some_actions() {
+    let bindings = KeyBindings::parse(r#"{
+        "buttons": { "a": ["KeyX", "Space"], "up": ["ArrowUp"] },
+        "hotkeys": { "fast_forward": ["ShiftLeft"] }
+    }"#).unwrap();
+    assert_eq!(bindings.get_action(KeyCode::KeyX), Some(KeyAction::Button(Button::A)));
+    assert_eq!(bindings.get_action(KeyCode::Space), Some(KeyAction::Button(Button::A)));
+    assert_eq!(bindings.get_action(KeyCode::ArrowUp), Some(KeyAction::Button(Button::Up)));
+    assert_eq!(bindings.get_action(KeyCode::ShiftLeft), Some(KeyAction::Hotkey(Hotkey::FastForward)));
+    // replaced, not added to
+    assert_eq!(bindings.get_action(KeyCode::KeyK), None);
+    assert_eq!(bindings.get_action(KeyCode::KeyW), None);
+    // left out, so still the default
+    assert_eq!(bindings.get_action(KeyCode::KeyS), Some(KeyAction::Button(Button::Down)));
+    assert_eq!(bindings.get_action(KeyCode::KeyP), Some(KeyAction::Hotkey(Hotkey::Pause)));
+}
+
+#[test]
+fn file_takes_keys_from_defaults() {
+    // K is a's by default, the file gives it to up and leaves a alone
+    let bindings = KeyBindings::parse(r#"{ "buttons": { "up": ["KeyK"] } }"#).unwrap();
+    assert_eq!(bindings.get_action(KeyCode::KeyK), Some(KeyAction::Button(Button::Up)));
+    // F5 is save state's, which comes after pause in the list and still loses it
+    let bindings = KeyBindings::parse(r#"{ "hotkeys": { "pause": ["F5"] } }"#).unwrap();
+    assert_eq!(bindings.get_action(KeyCode::F5), Some(KeyAction::Hotkey(Hotkey::Pause)));
+}
+
+#[test]
+fn rejects_typos() {
+    assert!(KeyBindings::parse(r#"{ "buttons": { "a": ["KeyXX"] } }"#).is_err());
+    assert!(KeyBindings::parse(r#"{ "buttons": { "turbo_a": ["KeyX"] } }"#).is_err());
+}
+
+// the mtime is bumped by hand, two writes in the same tick would otherwise look unchanged
+fn rewrite(file: &str, json: &str, seconds: u64) {
+    fs::write(file, json).unwrap();
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+    fs::File::options().write(true).open(file).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn reloads_when_the_file_changes() {
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let file = format!("{}/reload.json", OUTPUT_DIR);
+    rewrite(&file, r#"{ "buttons": { "a": ["KeyX"] } }"#, 1_000);
+    let mut bindings = KeyBindings::load(&file).unwrap();
+    assert_eq!(bindings.get_action(KeyCode::KeyX), Some(KeyAction::Button(Button::A)));
+    assert!(!bindings.reload_if_changed());
+
+    rewrite(&file, r#"{ "buttons": { "a": ["KeyZ"] } }"#, 2_000);
+    assert!(bindings.reload_if_changed());
+    assert_eq!(bindings.get_action(KeyCode::KeyZ), Some(KeyAction::Button(Button::A)));
+    assert_eq!(bindings.get_action(KeyCode::KeyX), None);
+    assert!(!bindings.reload_if_changed());
+
+    // a broken edit keeps the old bindings, and isn't retried until the next save
+    rewrite(&file, r#"{ "buttons": { "a": ["KeyZZ"] } }"#, 3_000);
+    assert!(!bindings.reload_if_changed());
+    assert_eq!(bindings.get_action(KeyCode::KeyZ), Some(KeyAction::Button(Button::A)));
+    rewrite(&file, r#"{ "buttons": { "a": ["KeyZ"], "#, 4_000);
+    assert!(!bindings.reload_if_changed());
+    assert_eq!(bindings.get_action(KeyCode::KeyZ), Some(KeyAction::Button(Button::A)));
+
+    rewrite(&file, r#"{ "buttons": { "a": ["KeyQ"] } }"#, 5_000);
+    assert!(bindings.reload_if_changed());
+    assert_eq!(bindings.get_action(KeyCode::KeyQ), Some(KeyAction::Button(Button::A)));
+}
@@ -0,0 +1,40 @@
+// illegal opcodes lock the cpu up until a reset
+// cargo test --test lock_up
+
+use std::fs;
+use std::sync::mpsc;
+
+use gbemu::gb::cpu::LockUp;
+use gbemu::gb::emu::EmuEvent;
+use gbemu::gb::headless::Headless;
+
+const OUTPUT_DIR: &str = "target/lock_up";
+
+// tetris with the entry point swapped for NOP / D3, the header is untouched so the boot rom still hands over
+fn make_rom() -> String {
+    let mut data = fs::read("tetris.gb").unwrap();
+    data[0x100..0x102].copy_from_slice(&[0x00, 0xD3]);
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let file = format!("{}/illegal.gb", OUTPUT_DIR);
+    fs::write(&file, data).unwrap();
+    file
+}
+
+#[test]
+fn illegal_opcode() {
+    let mut headless = Headless::new(&make_rom());
+    let (tx, rx) = mpsc::channel();
+    headless.emu.event_tx = Some(tx);
+    assert!(headless.run_until(10 * 1_048_576, |emu| emu.cpu.locked_up.is_some()), "never locked up");
+    let lock_up = LockUp { pc: 0x0101, bank: 0, opcode: 0xD3 };
+    assert_eq!(headless.emu.cpu.locked_up, Some(lock_up));
+    assert_eq!(rx.try_recv(), Ok(EmuEvent::LockUp(lock_up)));
+
+    // time goes on but the cpu doesn't, and it's only reported once
+    let pc = headless.emu.cpu.registers.get_pc();
+    let mcycles = headless.mcycles();
+    assert!(headless.run_frames(2));
+    assert!(headless.mcycles() > mcycles);
+    assert_eq!(headless.emu.cpu.registers.get_pc(), pc);
+    assert!(rx.try_recv().is_err());
+}
@@ -0,0 +1,129 @@
+// one test per mooneye rom that's meant for the dmg, run with
+// cargo test --test mooneye
+// roms in tests/mooneye_known_failures.txt are expected to fail, if one starts passing
+// its test fails so it gets taken off the list
+
+use gbemu::gb::mooneye::*;
+
+fn check(rom: &str) {
+    let is_known_failure = load_known_failures(KNOWN_FAILURES_FILE).iter().any(|known| known == rom);
+    let result = run_mooneye_rom(&format!("{}/{}", MOONEYE_DIR, rom), DEFAULT_TIMEOUT_MCYCLES);
+    if is_known_failure {
+        assert!(!result.is_pass(), "{} passes now, remove it from {}", rom, KNOWN_FAILURES_FILE);
+    } else {
+        assert!(result.is_pass(), "{}: {}", rom, result.describe());
+    }
+}
+
+#[test]
+fn unsupported_mapper_is_reported() {
+    let result = run_mooneye_rom(&format!("{}/acceptance/oam_dma/sources-GS.gb", MOONEYE_DIR), DEFAULT_TIMEOUT_MCYCLES);
+    assert_eq!(result, MooneyeResult::Unsupported(String::from("MBC5_RAM_BATT carts aren't supported yet")));
+}
+
+macro_rules! mooneye_tests {
+    ($($name:ident => $rom:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check($rom);
+            }
+        )*
+    };
+}
+
+mooneye_tests! {
+    acceptance_add_sp_e_timing => "acceptance/add_sp_e_timing.gb",
+    acceptance_bits_mem_oam => "acceptance/bits/mem_oam.gb",
+    acceptance_bits_reg_f => "acceptance/bits/reg_f.gb",
+    acceptance_bits_unused_hwio_gs => "acceptance/bits/unused_hwio-GS.gb",
+    acceptance_boot_div_dmgabcmgb => "acceptance/boot_div-dmgABCmgb.gb",
+    acceptance_boot_hwio_dmgabcmgb => "acceptance/boot_hwio-dmgABCmgb.gb",
+    acceptance_boot_regs_dmgabc => "acceptance/boot_regs-dmgABC.gb",
+    acceptance_call_cc_timing => "acceptance/call_cc_timing.gb",
+    acceptance_call_cc_timing2 => "acceptance/call_cc_timing2.gb",
+    acceptance_call_timing => "acceptance/call_timing.gb",
+    acceptance_call_timing2 => "acceptance/call_timing2.gb",
+    acceptance_di_timing_gs => "acceptance/di_timing-GS.gb",
+    acceptance_div_timing => "acceptance/div_timing.gb",
+    acceptance_ei_sequence => "acceptance/ei_sequence.gb",
+    acceptance_ei_timing => "acceptance/ei_timing.gb",
+    acceptance_halt_ime0_ei => "acceptance/halt_ime0_ei.gb",
+    acceptance_halt_ime0_nointr_timing => "acceptance/halt_ime0_nointr_timing.gb",
+    acceptance_halt_ime1_timing => "acceptance/halt_ime1_timing.gb",
+    acceptance_halt_ime1_timing2_gs => "acceptance/halt_ime1_timing2-GS.gb",
+    acceptance_if_ie_registers => "acceptance/if_ie_registers.gb",
+    acceptance_instr_daa => "acceptance/instr/daa.gb",
+    acceptance_interrupts_ie_push => "acceptance/interrupts/ie_push.gb",
+    acceptance_intr_timing => "acceptance/intr_timing.gb",
+    acceptance_jp_cc_timing => "acceptance/jp_cc_timing.gb",
+    acceptance_jp_timing => "acceptance/jp_timing.gb",
+    acceptance_ld_hl_sp_e_timing => "acceptance/ld_hl_sp_e_timing.gb",
+    acceptance_oam_dma_basic => "acceptance/oam_dma/basic.gb",
+    acceptance_oam_dma_reg_read => "acceptance/oam_dma/reg_read.gb",
+    acceptance_oam_dma_sources_gs => "acceptance/oam_dma/sources-GS.gb",
+    acceptance_oam_dma_restart => "acceptance/oam_dma_restart.gb",
+    acceptance_oam_dma_start => "acceptance/oam_dma_start.gb",
+    acceptance_oam_dma_timing => "acceptance/oam_dma_timing.gb",
+    acceptance_pop_timing => "acceptance/pop_timing.gb",
+    acceptance_ppu_hblank_ly_scx_timing_gs => "acceptance/ppu/hblank_ly_scx_timing-GS.gb",
+    acceptance_ppu_intr_1_2_timing_gs => "acceptance/ppu/intr_1_2_timing-GS.gb",
+    acceptance_ppu_intr_2_0_timing => "acceptance/ppu/intr_2_0_timing.gb",
+    acceptance_ppu_intr_2_mode0_timing => "acceptance/ppu/intr_2_mode0_timing.gb",
+    acceptance_ppu_intr_2_mode0_timing_sprites => "acceptance/ppu/intr_2_mode0_timing_sprites.gb",
+    acceptance_ppu_intr_2_mode3_timing => "acceptance/ppu/intr_2_mode3_timing.gb",
+    acceptance_ppu_intr_2_oam_ok_timing => "acceptance/ppu/intr_2_oam_ok_timing.gb",
+    acceptance_ppu_lcdon_timing_gs => "acceptance/ppu/lcdon_timing-GS.gb",
+    acceptance_ppu_lcdon_write_timing_gs => "acceptance/ppu/lcdon_write_timing-GS.gb",
+    acceptance_ppu_stat_irq_blocking => "acceptance/ppu/stat_irq_blocking.gb",
+    acceptance_ppu_stat_lyc_onoff => "acceptance/ppu/stat_lyc_onoff.gb",
+    acceptance_ppu_vblank_stat_intr_gs => "acceptance/ppu/vblank_stat_intr-GS.gb",
+    acceptance_push_timing => "acceptance/push_timing.gb",
+    acceptance_rapid_di_ei => "acceptance/rapid_di_ei.gb",
+    acceptance_ret_cc_timing => "acceptance/ret_cc_timing.gb",
+    acceptance_ret_timing => "acceptance/ret_timing.gb",
+    acceptance_reti_intr_timing => "acceptance/reti_intr_timing.gb",
+    acceptance_reti_timing => "acceptance/reti_timing.gb",
+    acceptance_rst_timing => "acceptance/rst_timing.gb",
+    acceptance_serial_boot_sclk_align_dmgabcmgb => "acceptance/serial/boot_sclk_align-dmgABCmgb.gb",
+    acceptance_timer_div_write => "acceptance/timer/div_write.gb",
+    acceptance_timer_rapid_toggle => "acceptance/timer/rapid_toggle.gb",
+    acceptance_timer_tim00 => "acceptance/timer/tim00.gb",
+    acceptance_timer_tim00_div_trigger => "acceptance/timer/tim00_div_trigger.gb",
+    acceptance_timer_tim01 => "acceptance/timer/tim01.gb",
+    acceptance_timer_tim01_div_trigger => "acceptance/timer/tim01_div_trigger.gb",
+    acceptance_timer_tim10 => "acceptance/timer/tim10.gb",
+    acceptance_timer_tim10_div_trigger => "acceptance/timer/tim10_div_trigger.gb",
+    acceptance_timer_tim11 => "acceptance/timer/tim11.gb",
+    acceptance_timer_tim11_div_trigger => "acceptance/timer/tim11_div_trigger.gb",
+    acceptance_timer_tima_reload => "acceptance/timer/tima_reload.gb",
+    acceptance_timer_tima_write_reloading => "acceptance/timer/tima_write_reloading.gb",
+    acceptance_timer_tma_write_reloading => "acceptance/timer/tma_write_reloading.gb",
+    emu_only_mbc1_bits_bank1 => "emulator-only/mbc1/bits_bank1.gb",
+    emu_only_mbc1_bits_bank2 => "emulator-only/mbc1/bits_bank2.gb",
+    emu_only_mbc1_bits_mode => "emulator-only/mbc1/bits_mode.gb",
+    emu_only_mbc1_bits_ramg => "emulator-only/mbc1/bits_ramg.gb",
+    emu_only_mbc1_multicart_rom_8mb => "emulator-only/mbc1/multicart_rom_8Mb.gb",
+    emu_only_mbc1_ram_256kb => "emulator-only/mbc1/ram_256kb.gb",
+    emu_only_mbc1_ram_64kb => "emulator-only/mbc1/ram_64kb.gb",
+    emu_only_mbc1_rom_16mb => "emulator-only/mbc1/rom_16Mb.gb",
+    emu_only_mbc1_rom_1mb => "emulator-only/mbc1/rom_1Mb.gb",
+    emu_only_mbc1_rom_2mb => "emulator-only/mbc1/rom_2Mb.gb",
+    emu_only_mbc1_rom_4mb => "emulator-only/mbc1/rom_4Mb.gb",
+    emu_only_mbc1_rom_512kb => "emulator-only/mbc1/rom_512kb.gb",
+    emu_only_mbc1_rom_8mb => "emulator-only/mbc1/rom_8Mb.gb",
+    emu_only_mbc2_bits_ramg => "emulator-only/mbc2/bits_ramg.gb",
+    emu_only_mbc2_bits_romb => "emulator-only/mbc2/bits_romb.gb",
+    emu_only_mbc2_bits_unused => "emulator-only/mbc2/bits_unused.gb",
+    emu_only_mbc2_ram => "emulator-only/mbc2/ram.gb",
+    emu_only_mbc2_rom_1mb => "emulator-only/mbc2/rom_1Mb.gb",
+    emu_only_mbc2_rom_2mb => "emulator-only/mbc2/rom_2Mb.gb",
+    emu_only_mbc2_rom_512kb => "emulator-only/mbc2/rom_512kb.gb",
+    emu_only_mbc5_rom_16mb => "emulator-only/mbc5/rom_16Mb.gb",
+    emu_only_mbc5_rom_1mb => "emulator-only/mbc5/rom_1Mb.gb",
+    emu_only_mbc5_rom_2mb => "emulator-only/mbc5/rom_2Mb.gb",
+    emu_only_mbc5_rom_4mb => "emulator-only/mbc5/rom_4Mb.gb",
+    emu_only_mbc5_rom_512kb => "emulator-only/mbc5/rom_512kb.gb",
+    emu_only_mbc5_rom_8mb => "emulator-only/mbc5/rom_8Mb.gb",
+    madness_mgb_oam_dma_halt_sprites => "madness/mgb_oam_dma_halt_sprites.gb",
+}
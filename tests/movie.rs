@@ -0,0 +1,138 @@
+// input movies, recording one and playing it back has to end up in the exact same state
+// cargo test --test movie
+
+use std::fs;
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::joypad::Button;
+use gbemu::gb::mbc::OpSource;
+use gbemu::gb::movie::*;
+use gbemu::gb::png::crc32;
+
+const ROM: &str = "tetris.gb";
+const FRAMES: u64 = 700; // the boot rom is about 330 of them
+
+fn get_movie_file(name: &str) -> String {
+    let _ = fs::create_dir_all("target/movie");
+    format!("target/movie/{}.gbm", name)
+}
+
+// cpu, frame and the bits of memory tetris keeps its game in
+fn get_state(headless: &Headless) -> (u64, [u16; 6], Vec<u8>, Vec<u8>) {
+    let emu = &headless.emu;
+    let r = &emu.cpu.registers;
+    let registers = [r.get_af(), r.get_bc(), r.get_de(), r.get_hl(), r.get_sp(), r.get_pc()];
+    let ram = (0xC000..0xE000u16).map(|address| emu.mbc.read(address, OpSource::Debugger)).collect();
+    (emu.cpu.total_mcycles, registers, ram, headless.copy_game_buffer())
+}
+
+// mashes start and a few directions on frames that don't line up with anything in particular
+fn get_input(frame: u64) -> Vec<(Button, bool)> {
+    match frame {
+        400 => vec![(Button::Start, true)],
+        405 => vec![(Button::Start, false)],
+        450 => vec![(Button::Start, true)],
+        453 => vec![(Button::Start, false)],
+        500 => vec![(Button::Left, true), (Button::A, true)],
+        530 => vec![(Button::Left, false)],
+        560 => vec![(Button::A, false), (Button::Down, true)],
+        650 => vec![(Button::Down, false)],
+        _ => vec![],
+    }
+}
+
+#[test]
+fn playback_matches_recording() {
+    let file = get_movie_file("playback");
+    let mut recording = Headless::new(ROM);
+    recording.emu.start_movie_recording(&file, ROM).unwrap();
+    for frame in 0..FRAMES {
+        for (button, is_held) in get_input(frame) {
+            recording.emu.set_button(button, is_held);
+        }
+        assert!(recording.run_frames(1));
+    }
+    // what closing the window does, it has to leave the whole movie on disk
+    recording.emu.shut_down();
+    let recorded = get_state(&recording);
+
+    let movie = Movie::load(&file).unwrap();
+    assert_eq!(movie.rom_crc32, crc32(&fs::read(ROM).unwrap()));
+    assert!(movie.frames.iter().any(|&buttons| buttons != 0), "no input made it into the movie");
+
+    let mut playback = Headless::new(ROM);
+    playback.emu.play_movie(movie).unwrap();
+    assert!(playback.run_frames(FRAMES));
+    let played = get_state(&playback);
+    assert_eq!(recorded.0, played.0, "mcycles differ");
+    assert_eq!(recorded.1, played.1, "registers differ");
+    assert!(recorded.2 == played.2, "wram differs");
+    assert!(recorded.3 == played.3, "screen differs");
+}
+
+#[test]
+fn rejects_other_rom() {
+    let file = get_movie_file("other_rom");
+    let mut recording = Headless::new(ROM);
+    recording.emu.start_movie_recording(&file, ROM).unwrap();
+    recording.emu.stop_movie();
+
+    let mut playback = Headless::new("drmario.gb");
+    let err = playback.emu.play_movie(Movie::load(&file).unwrap()).unwrap_err();
+    assert!(err.to_string().contains("crc32"), "{}", err);
+}
+
+#[test]
+fn frame_round_trip() {
+    for buttons in 0..=255u8 {
+        assert_eq!(parse_frame(&format_frame(buttons)), Some(buttons));
+    }
+    assert_eq!(format_frame(Button::Up.get_bit() | Button::A.get_bit()), "U......A");
+    assert_eq!(parse_frame("U.....BX"), None);
+}
+
+#[test]
+fn start_lines() {
+    let text = format!("{}\nrom tetris.gb\ncrc32 1A2B3C4D\nstart state 00FF\nframes\n........\n", MOVIE_HEADER);
+    assert_eq!(Movie::parse(&text).unwrap().start, MovieStart::State(vec![0x00, 0xFF]));
+    assert_eq!(MovieStart::State(vec![0x00, 0xFF]).format(), "state 00FF");
+    let power_on = text.replace("start state 00FF", "start power-on");
+    assert_eq!(Movie::parse(&power_on).unwrap().start, MovieStart::PowerOn);
+    for bad in ["start state", "start state 0F0", "start state XY", "start reset"] {
+        assert!(Movie::parse(&text.replace("start state 00FF", bad)).is_err(), "{}", bad);
+    }
+}
+
+// recording starts partway into a game, so the movie carries a save state of that point
+#[test]
+fn playback_from_save_state() {
+    let file = get_movie_file("save_state");
+    let mut recording = Headless::new(ROM);
+    for frame in 0..450 {
+        for (button, is_held) in get_input(frame) {
+            recording.emu.set_button(button, is_held);
+        }
+        assert!(recording.run_frames(1));
+    }
+    recording.emu.start_movie_recording(&file, ROM).unwrap();
+    for frame in 450..FRAMES {
+        for (button, is_held) in get_input(frame) {
+            recording.emu.set_button(button, is_held);
+        }
+        assert!(recording.run_frames(1));
+    }
+    recording.emu.stop_movie();
+    let recorded = get_state(&recording);
+
+    let movie = Movie::load(&file).unwrap();
+    assert!(matches!(movie.start, MovieStart::State(_)));
+    assert_eq!(movie.frames.len() as u64, FRAMES - 450);
+    let mut playback = Headless::new(ROM);
+    playback.emu.play_movie(movie).unwrap();
+    assert!(playback.run_frames(FRAMES - 450));
+    let played = get_state(&playback);
+    assert_eq!(recorded.0, played.0, "mcycles differ");
+    assert_eq!(recorded.1, played.1, "registers differ");
+    assert!(recorded.2 == played.2, "wram differs");
+    assert!(recorded.3 == played.3, "screen differs");
+}
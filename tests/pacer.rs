@@ -0,0 +1,51 @@
+// frame pacing, only checks that frames aren't let through early since a busy host can always be late
+// cargo test --test pacer
+
+use std::time::{Duration, Instant};
+
+use gbemu::gb::pacer::*;
+use gbemu::gb::constants::MCYCLES_PER_FRAME;
+
+fn run_frames(pacer: &mut FramePacer, frames: u64) -> Duration {
+    let start = Instant::now();
+    // a few mcycles at a time like instructions, frames don't end on a step boundary
+    for _ in 0..frames * MCYCLES_PER_FRAME / 4 {
+        pacer.add_mcycles(4);
+    }
+    start.elapsed()
+}
+
+#[test]
+fn real_time() {
+    let mut pacer = FramePacer::new(Speed::Times(1.0));
+    // 30 frames at 70224 / 4194304 s each
+    assert!(run_frames(&mut pacer, 30) >= Duration::from_micros(502_300));
+}
+
+#[test]
+fn multiplied() {
+    let mut pacer = FramePacer::new(Speed::Times(4.0));
+    let fast = run_frames(&mut pacer, 60);
+    assert!(fast >= Duration::from_micros(251_100), "{:?}", fast);
+
+    pacer.set_speed(Speed::Times(0.5));
+    let slow = run_frames(&mut pacer, 6);
+    assert!(slow >= Duration::from_micros(200_900), "{:?}", slow);
+}
+
+#[test]
+fn unthrottled() {
+    let mut pacer = FramePacer::new(Speed::Unthrottled);
+    assert!(run_frames(&mut pacer, 600) < Duration::from_secs(5));
+}
+
+#[test]
+fn parse() {
+    assert_eq!(Speed::parse("2"), Some(Speed::Times(2.0)));
+    assert_eq!(Speed::parse("4x"), Some(Speed::Times(4.0)));
+    assert_eq!(Speed::parse("0.25"), Some(Speed::Times(0.25)));
+    assert_eq!(Speed::parse("unthrottled"), Some(Speed::Unthrottled));
+    assert_eq!(Speed::parse("0"), None);
+    assert_eq!(Speed::parse("-1"), None);
+    assert_eq!(Speed::parse("fast"), None);
+}
@@ -0,0 +1,60 @@
+// y4m recording, one video frame per emulated frame, blank ones while the lcd is off
+// cargo test --test recorder
+
+use std::fs;
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+use gbemu::gb::constants::MCYCLES_PER_FRAME;
+use gbemu::gb::recorder::FRAME_RATE;
+
+const OUTPUT_DIR: &str = "target/recorder";
+const FRAME_SIZE: usize = 160 * 144 * 3;
+
+// tetris with the entry point swapped for JR -2, the boot rom hands over and the cpu spins with the lcd on
+fn make_rom() -> String {
+    let mut data = fs::read("tetris.gb").unwrap();
+    data[0x100..0x102].copy_from_slice(&[0x18, 0xFE]);
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let file = format!("{}/spin.gb", OUTPUT_DIR);
+    fs::write(&file, data).unwrap();
+    file
+}
+
+#[test]
+fn records_frames() {
+    let mut headless = Headless::new(&make_rom());
+    // the boot rom starts with the lcd off, wait for it to draw before recording
+    assert!(headless.run_frames(1));
+    let file = format!("{}/video.y4m", OUTPUT_DIR);
+    headless.emu.start_recording(&file).unwrap();
+    assert!(headless.run_frames(3));
+    assert_eq!(headless.emu.recorder.as_ref().unwrap().frames, 3);
+
+    // lcd off, frames keep coming every 17556 mcycles
+    let lcdc = headless.emu.mbc.read(0xFF40, OpSource::Debugger);
+    headless.emu.mbc.write(0xFF40, lcdc & 0x7F, OpSource::Debugger);
+    let start = headless.mcycles();
+    headless.run_until(4 * MCYCLES_PER_FRAME + MCYCLES_PER_FRAME / 2, |_| false);
+    assert!(headless.mcycles() - start >= 4 * MCYCLES_PER_FRAME);
+    assert_eq!(headless.frames, 4);
+    assert_eq!(headless.emu.recorder.as_ref().unwrap().frames, 7);
+    headless.emu.stop_recording();
+    assert!(headless.emu.recorder.is_none());
+
+    let data = fs::read(&file).unwrap();
+    let header = format!("YUV4MPEG2 W160 H144 F{} Ip A1:1 C444 XCOLORRANGE=FULL\n", FRAME_RATE);
+    assert!(data.starts_with(header.as_bytes()));
+    let frames: Vec<&[u8]> = data[header.len()..].chunks(6 + FRAME_SIZE).collect();
+    assert_eq!(frames.len(), 7);
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.len(), 6 + FRAME_SIZE, "frame {} is cut short", i);
+        assert_eq!(&frame[..6], b"FRAME\n", "frame {}", i);
+    }
+    // the blank frames are white, full range y and neutral chroma
+    for frame in &frames[3..] {
+        let (y, uv) = frame[6..].split_at(FRAME_SIZE / 3);
+        assert!(y.iter().all(|&byte| byte == 0xFF));
+        assert!(uv.iter().all(|&byte| byte == 0x80));
+    }
+}
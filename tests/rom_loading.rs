@@ -0,0 +1,126 @@
+// swapping cartridges at runtime and battery saves
+// cargo test --test rom_loading
+
+use std::fs;
+
+use gbemu::gb::emu::get_save_file;
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+
+const OUTPUT_DIR: &str = "target/rom_loading";
+
+fn get_state(headless: &Headless) -> (u64, u16, Vec<u8>) {
+    let emu = &headless.emu;
+    let ram = (0xC000..0xE000u16).map(|address| emu.mbc.read(address, OpSource::Debugger)).collect();
+    (emu.cpu.total_mcycles, emu.cpu.registers.get_pc(), ram)
+}
+
+// tetris with the header changed to another cart type and ram size, checksum fixed so the boot rom takes it
+fn make_rom(name: &str, rom_type: u8, ram_size: u8) -> String {
+    let mut data = fs::read("tetris.gb").unwrap();
+    data[0x147] = rom_type;
+    data[0x149] = ram_size;
+    data[0x14D] = data[0x134..0x14D].iter().fold(0u8, |sum, &byte| sum.wrapping_sub(byte).wrapping_sub(1));
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let file = format!("{}/{}.gb", OUTPUT_DIR, name);
+    fs::write(&file, data).unwrap();
+    let _ = fs::remove_file(get_save_file(&file));
+    file
+}
+
+#[test]
+fn swap_starts_from_power_on() {
+    let mut fresh = Headless::new("drmario.gb");
+    assert!(fresh.run_frames(400));
+    let expected = get_state(&fresh);
+
+    let mut swapped = Headless::new("tetris.gb");
+    assert!(swapped.run_frames(450));
+    swapped.emu.load_rom("drmario.gb").unwrap();
+    assert_eq!(swapped.emu.cpu.total_mcycles, 0);
+    assert_eq!(swapped.emu.rom_file.as_deref(), Some("drmario.gb"));
+    swapped.frames = 0;
+    assert!(swapped.run_frames(400));
+    assert!(get_state(&swapped) == expected, "drmario ran differently after tetris");
+}
+
+#[test]
+fn bad_rom_keeps_the_current_one() {
+    let mut headless = Headless::new("tetris.gb");
+    assert!(headless.run_frames(10));
+    let before = get_state(&headless);
+
+    assert!(headless.emu.load_rom("no-such-rom.gb").is_err());
+    // mbc5 carts aren't supported yet
+    assert!(headless.emu.load_rom(&make_rom("mbc5", 0x19, 0x00)).is_err());
+    assert_eq!(headless.emu.rom_file.as_deref(), Some("tetris.gb"));
+    assert!(get_state(&headless) == before);
+}
+
+#[test]
+fn save_ram_round_trip() {
+    // mbc1 + 8KB ram + battery
+    let rom = make_rom("battery", 0x03, 0x02);
+    let mut headless = Headless::new(&rom);
+    headless.emu.mbc.write(0x0000, 0x0A, OpSource::CPU); // ram enable
+    headless.emu.mbc.write(0xA000, 0x12, OpSource::CPU);
+    headless.emu.mbc.write(0xBFFF, 0x34, OpSource::CPU);
+
+    // swapping writes the old cart's save
+    headless.emu.load_rom("tetris.gb").unwrap();
+    let save = fs::read(get_save_file(&rom)).unwrap();
+    assert_eq!(save.len(), 0x2000);
+    assert_eq!((save[0], save[0x1FFF]), (0x12, 0x34));
+
+    // and loading it again picks the save up
+    headless.emu.load_rom(&rom).unwrap();
+    headless.emu.mbc.write(0x0000, 0x0A, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xA000, OpSource::CPU), 0x12);
+    assert_eq!(headless.emu.mbc.read(0xBFFF, OpSource::CPU), 0x34);
+}
+
+#[test]
+fn mbc3_save_ram_round_trip() {
+    // mbc3 + 32KB ram + battery, copied so the .sav lands in target
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let rom = format!("{}/pokemon.gb", OUTPUT_DIR);
+    fs::copy("pokemon_red_gbc.gb", &rom).unwrap();
+    let _ = fs::remove_file(get_save_file(&rom));
+    let mut headless = Headless::new(&rom);
+    // 0000-00FF writes go to the boot rom while it's mapped
+    assert!(headless.run_until(10 * 1_048_576, |emu| emu.mbc.hw_reg.boot_rom_control != 0));
+    assert!(headless.run_frames(60));
+    // the game keeps its own data in the first bank, so write to the last two
+    headless.emu.mbc.write(0x0000, 0x0A, OpSource::CPU);
+    headless.emu.mbc.write(0x4000, 0x02, OpSource::CPU);
+    headless.emu.mbc.write(0xA000, 0x12, OpSource::CPU);
+    headless.emu.mbc.write(0x4000, 0x03, OpSource::CPU);
+    headless.emu.mbc.write(0xBFFF, 0x34, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xBFFF, OpSource::CPU), 0x34);
+    headless.emu.mbc.write(0x4000, 0x02, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xA000, OpSource::CPU), 0x12);
+    // with the ram disabled nothing gets through
+    headless.emu.mbc.write(0x0000, 0x00, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xA000, OpSource::CPU), 0xFF);
+
+    headless.emu.shut_down();
+    let save = fs::read(get_save_file(&rom)).unwrap();
+    assert_eq!(save.len(), 0x8000);
+    assert_eq!((save[0x4000], save[0x7FFF]), (0x12, 0x34));
+
+    let mut headless = Headless::new(&rom);
+    assert!(headless.run_until(10 * 1_048_576, |emu| emu.mbc.hw_reg.boot_rom_control != 0));
+    headless.emu.mbc.write(0x0000, 0x0A, OpSource::CPU);
+    headless.emu.mbc.write(0x4000, 0x03, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xBFFF, OpSource::CPU), 0x34);
+    headless.emu.mbc.write(0x4000, 0x02, OpSource::CPU);
+    assert_eq!(headless.emu.mbc.read(0xA000, OpSource::CPU), 0x12);
+}
+
+#[test]
+fn no_save_without_battery() {
+    let mut headless = Headless::new("tetris.gb");
+    headless.emu.shut_down();
+    assert!(headless.emu.is_shut_down);
+    assert!(!std::path::Path::new(&get_save_file("tetris.gb")).exists());
+}
@@ -0,0 +1,85 @@
+// save states, loading one has to carry on exactly like the emu it came from
+// cargo test --test savestate
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::OpSource;
+use gbemu::gb::savestate::*;
+
+const ROM: &str = "tetris.gb";
+
+fn get_state(headless: &Headless) -> (u64, u16, Vec<u8>, Vec<u8>) {
+    let emu = &headless.emu;
+    let ram = (0xC000..0xE000u16).map(|address| emu.mbc.read(address, OpSource::Debugger)).collect();
+    (emu.cpu.total_mcycles, emu.cpu.registers.get_pc(), ram, headless.copy_game_buffer())
+}
+
+#[test]
+fn load_carries_on_the_same() {
+    let mut original = Headless::new(ROM);
+    assert!(original.run_frames(400));
+    // partway into a frame, not just on a frame boundary
+    original.run_until(1234, |_| false);
+    let state = original.emu.save_state();
+
+    let mut loaded = Headless::new(ROM);
+    loaded.emu.load_state(&state).unwrap();
+    assert_eq!(get_state(&loaded).0, get_state(&original).0);
+    assert!(original.run_frames(60));
+    assert!(loaded.run_frames(60));
+    assert!(get_state(&loaded) == get_state(&original), "emus went different ways after the load");
+
+    // the state loads again on the one that was running, it goes back
+    original.emu.load_state(&state).unwrap();
+    assert!(original.run_frames(60));
+    assert!(get_state(&loaded) == get_state(&original));
+}
+
+#[test]
+fn rejects_broken_states() {
+    let state = Headless::new("drmario.gb").emu.save_state();
+    let mut headless = Headless::new(ROM);
+    assert!(headless.run_frames(10));
+    let before = get_state(&headless);
+    let err = headless.emu.load_state(&state).unwrap_err();
+    assert!(err.contains("crc32"), "{}", err);
+    assert!(headless.emu.load_state(b"not a state").is_err());
+
+    // cut short after the header checks, what was read so far is put back
+    let state = decompress(&headless.emu.save_state()).unwrap();
+    let err = headless.emu.load_state(&compress(&state[..state.len() / 2])).unwrap_err();
+    assert!(err.contains("ends early"), "{}", err);
+    assert!(get_state(&headless) == before);
+}
+
+#[test]
+fn packbits_round_trip() {
+    let mut data = vec![0u8; 1000];
+    data.extend((0..300).map(|i| i as u8));
+    data.extend([7, 7, 1, 7, 7, 7, 2]);
+    let packed = compress(&data);
+    assert!(packed.len() < data.len());
+    assert_eq!(decompress(&packed).unwrap(), data);
+    assert_eq!(decompress(&compress(&[])).unwrap(), Vec::<u8>::new());
+    assert!(decompress(&[0x80]).is_err());
+    assert!(decompress(&[0x05, 1, 2]).is_err());
+}
+
+// what the save and load state hotkeys do
+#[test]
+fn state_file_next_to_the_rom() {
+    let _ = std::fs::create_dir_all("target/savestate");
+    std::fs::copy(ROM, "target/savestate/tetris.gb").unwrap();
+    let _ = std::fs::remove_file("target/savestate/tetris.state");
+    let mut headless = Headless::new("target/savestate/tetris.gb");
+    assert!(headless.run_frames(100));
+    headless.emu.save_state_file();
+    let saved = get_state(&headless);
+    assert!(std::fs::metadata("target/savestate/tetris.state").is_ok());
+
+    assert!(headless.run_frames(100));
+    headless.emu.load_state_file();
+    // the screen isn't in a state, the next frame draws it again
+    let loaded = get_state(&headless);
+    assert_eq!((loaded.0, loaded.1), (saved.0, saved.1));
+    assert!(loaded.2 == saved.2, "wram differs");
+}
@@ -0,0 +1,94 @@
+// screenshots, their file names and the png round trip
+// cargo test --test screenshot
+
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use gbemu::gb::headless::Headless;
+use gbemu::gb::png::*;
+use gbemu::gb::screenshot::*;
+
+const OUTPUT_DIR: &str = "target/screenshot";
+
+#[test]
+fn civil_dates() {
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+    assert_eq!(civil_from_days(20_745), (2026, 10, 19));
+    // 2100 isn't a leap year
+    assert_eq!(civil_from_days(47_540), (2100, 2, 28));
+    assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+}
+
+#[test]
+fn timestamp() {
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let stamp = get_timestamp();
+    let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    // yyyymmdd-hhmmss-mmm
+    assert_eq!(stamp.len(), 19, "{}", stamp);
+    assert_eq!(stamp.as_bytes()[8], b'-');
+    assert_eq!(stamp.as_bytes()[15], b'-');
+    assert!(stamp.chars().filter(|&c| c != '-').all(|c| c.is_ascii_digit()), "{}", stamp);
+    // it's the date of one of the seconds around the call, in utc
+    let dates: Vec<String> = [before, after].iter().map(|secs| {
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        format!("{:04}{:02}{:02}", year, month, day)
+    }).collect();
+    assert!(dates.contains(&stamp[..8].to_string()), "{} {:?}", stamp, dates);
+}
+
+#[test]
+fn scaling() {
+    // 2x1, red then blue
+    let rgba = [255, 0, 0, 255, 0, 0, 255, 255];
+    assert_eq!(scale_rgba(&rgba, 2, 1, 1), rgba);
+    let scaled = scale_rgba(&rgba, 2, 1, 3);
+    assert_eq!(scaled.len(), 6 * 3 * 4);
+    for y in 0..3 {
+        for x in 0..6 {
+            let idx = (y * 6 + x) * 4;
+            let expected = if x < 3 { &rgba[..4] } else { &rgba[4..] };
+            assert_eq!(&scaled[idx..idx + 4], expected, "{},{}", x, y);
+        }
+    }
+}
+
+#[test]
+fn png_round_trip() {
+    let _ = fs::create_dir_all(OUTPUT_DIR);
+    let file = format!("{}/round-trip.png", OUTPUT_DIR);
+    let rgba: Vec<u8> = (0..16 * 8 * 4).map(|i| (i * 7) as u8).collect();
+    save_png(&file, 16, 8, &rgba).unwrap();
+    assert_eq!(load_png(&file).unwrap(), (16, 8, rgba));
+}
+
+#[test]
+fn png_is_stored_uncompressed() {
+    // bigger than one 65535 byte stored block
+    let rgba: Vec<u8> = (0..160 * 144 * 4).map(|i| (i % 251) as u8).collect();
+    let png = encode_png(160, 144, &rgba);
+    // signature, IHDR, IDAT with a filter byte per row and a 5 byte header per block, IEND
+    let rows = (160 * 4 + 1) * 144;
+    assert_eq!(png.len(), 8 + 25 + (12 + 2 + rows + 5 * 2 + 4) + 12);
+    assert_eq!(decode_png(&png).unwrap(), (160, 144, rgba));
+
+    let mut corrupt = png.clone();
+    corrupt[1000] ^= 0xFF;
+    assert!(decode_png(&corrupt).unwrap_err().contains("crc"));
+    assert!(decode_png(&png[..png.len() - 12]).is_err());
+}
+
+#[test]
+fn headless_screenshot() {
+    let mut headless = Headless::new("tetris.gb");
+    assert!(headless.run_frames(200));
+    let game = headless.copy_game_buffer();
+    let file = headless.screenshot(ScreenshotSource::Game, 2).unwrap();
+    let loaded = load_png(&file);
+    let _ = fs::remove_file(&file);
+    assert!(file.starts_with("gbemu-game-") && file.ends_with(".png"), "{}", file);
+    assert_eq!(loaded.unwrap(), (320, 288, scale_rgba(&game, 160, 144, 2)));
+}
@@ -0,0 +1,327 @@
+// super game boy packets, palettes, attributes and borders
+// cargo test --test sgb
+
+use std::sync::Mutex;
+
+use gbemu::gb::bios::ColorMode;
+use gbemu::gb::graphics::palette::PaletteColor;
+use gbemu::gb::graphics::pixel::GBPixel;
+use gbemu::gb::graphics::ppu::Ppu;
+use gbemu::gb::headless::Headless;
+use gbemu::gb::mbc::{Mbc, OpSource};
+use gbemu::gb::sgb::{Mask, Sgb, SGB_BUFFER_SIZE, SGB_WIDTH};
+
+mod common;
+use common::*;
+
+const SGB_ROM: &str = "tamagotchi.gb"; // the only cart here whose header asks for sgb functions
+
+const BLACK: [u8; 4] = [0, 0, 0, 255];
+
+// the dmg's greys for shades 0-3
+const SHADES: [[u8; 4]; 4] = [[255, 255, 255, 255], [192, 192, 192, 255], [96, 96, 96, 255], [0, 0, 0, 255]];
+
+// a reset pulse, 128 bits lsb first with P15 low for a 1 and P14 low for a 0, then the 0 stop bit
+fn get_joyp_writes(packet: &[u8]) -> Vec<u8> {
+    let mut writes = vec![0x00, 0x30];
+    for bit in 0..128 {
+        let is_one = packet.get(bit / 8).is_some_and(|byte| byte >> (bit % 8) & 1 != 0);
+        writes.extend([if is_one { 0x10 } else { 0x20 }, 0x30]);
+    }
+    writes.extend([0x20, 0x30]);
+    writes
+}
+
+fn send(sgb: &mut Sgb, packet: &[u8]) {
+    for byte in get_joyp_writes(packet) {
+        sgb.write_joyp(byte);
+    }
+}
+
+fn send_to_headless(headless: &mut Headless, packet: &[u8]) {
+    for byte in get_joyp_writes(packet) {
+        write(headless, 0xFF00, byte);
+    }
+}
+
+// PAL01 with color 0 red, palette 0 green/blue/white and palette 1 black/red/green
+const PAL01: [u8; 15] = [0x01, 0x1F, 0x00, 0xE0, 0x03, 0x00, 0x7C, 0xFF, 0x7F, 0x00, 0x00, 0x1F, 0x00, 0xE0, 0x03];
+
+#[test]
+fn packets_set_palettes() {
+    let mut sgb = Sgb::new(true);
+    send(&mut sgb, &PAL01);
+    assert_eq!(sgb.palettes[0], [0x001F, 0x03E0, 0x7C00, 0x7FFF]);
+    assert_eq!(sgb.palettes[1], [0x001F, 0x0000, 0x001F, 0x03E0]);
+    // color 0 is shared
+    assert_eq!(sgb.palettes[2][0], 0x001F);
+    assert_eq!(sgb.palettes[3][1], 0x6318);
+
+    // PAL12 with a 1 as the stop bit is thrown away
+    let mut sgb = Sgb::new(true);
+    let mut writes = get_joyp_writes(&[0x03 << 3 | 1, 0x00, 0x00, 0x1F, 0x00]);
+    let len = writes.len();
+    writes[len - 2] = 0x10;
+    for byte in writes {
+        sgb.write_joyp(byte);
+    }
+    assert_eq!(sgb.palettes[1][1], 0x6318);
+    assert!(sgb.packets.is_empty());
+
+    // a cart that doesn't ask for sgb functions gets nothing
+    let mut sgb = Sgb::new(false);
+    send(&mut sgb, &PAL01);
+    assert_eq!(sgb.palettes[0], [0x7FFF, 0x6318, 0x318C, 0x0000]);
+}
+
+#[test]
+fn header_enables_it() {
+    let mut headless = Headless::with_color_mode(SGB_ROM, Some(ColorMode::Super));
+    send_to_headless(&mut headless, &PAL01);
+    assert_eq!(headless.emu.mbc.sgb.as_ref().unwrap().palettes[0][1], 0x03E0);
+
+    let mut headless = Headless::with_color_mode(DMG_ROM, Some(ColorMode::Super));
+    send_to_headless(&mut headless, &PAL01);
+    let sgb = headless.emu.mbc.sgb.as_ref().unwrap();
+    assert!(!sgb.is_enabled);
+    assert_eq!(sgb.palettes[0][1], 0x6318);
+
+    // and it's only there when asked for
+    assert!(Headless::new(SGB_ROM).emu.mbc.sgb.is_none());
+}
+
+#[test]
+fn attributes() {
+    let mut sgb = Sgb::new(true);
+    let get = |sgb: &Sgb, x: usize, y: usize| sgb.attributes[y * 20 + x];
+
+    // ATTR_BLK: inside 1, edge 2, outside 3 around (2,2)-(5,4)
+    send(&mut sgb, &[0x04 << 3 | 1, 1, 0b111, 3 << 4 | 2 << 2 | 1, 2, 2, 5, 4]);
+    assert_eq!(get(&sgb, 0, 0), 3);
+    assert_eq!(get(&sgb, 2, 2), 2);
+    assert_eq!(get(&sgb, 5, 3), 2);
+    assert_eq!(get(&sgb, 3, 3), 1);
+    assert_eq!(get(&sgb, 6, 3), 3);
+    // only the inside, the edge goes with it
+    send(&mut sgb, &[0x04 << 3 | 1, 1, 0b001, 0, 2, 2, 5, 4]);
+    assert_eq!(get(&sgb, 2, 2), 0);
+    assert_eq!(get(&sgb, 0, 0), 3);
+
+    // ATTR_LIN: row 1 palette 2, column 19 palette 1
+    send(&mut sgb, &[0x05 << 3 | 1, 2, 0x80 | 2 << 5 | 1, 1 << 5 | 19]);
+    assert_eq!(get(&sgb, 0, 1), 2);
+    assert_eq!(get(&sgb, 19, 1), 1);
+    assert_eq!(get(&sgb, 19, 17), 1);
+    assert_eq!(get(&sgb, 0, 17), 3);
+
+    // ATTR_DIV: split at column 10, 1 left of it, 2 on it, 3 right of it
+    send(&mut sgb, &[0x06 << 3 | 1, 2 << 4 | 1 << 2 | 3, 10]);
+    assert_eq!(get(&sgb, 9, 5), 1);
+    assert_eq!(get(&sgb, 10, 17), 2);
+    assert_eq!(get(&sgb, 11, 0), 3);
+    // and at row 3
+    send(&mut sgb, &[0x06 << 3 | 1, 0x40 | 2 << 4 | 1 << 2 | 3, 3]);
+    assert_eq!(get(&sgb, 19, 2), 1);
+    assert_eq!(get(&sgb, 0, 3), 2);
+    assert_eq!(get(&sgb, 10, 4), 3);
+
+    // ATTR_CHR over 2 packets: 44 cells across from (18,0), wrapping onto the next rows
+    let mut packets = vec![0x07 << 3 | 2, 18, 0, 44, 0, 0];
+    packets.extend([0b00_01_10_11; 11]);
+    packets.resize(32, 0);
+    send(&mut sgb, &packets[..16]);
+    assert_eq!(get(&sgb, 18, 0), 1, "waits for the second packet");
+    send(&mut sgb, &packets[16..]);
+    assert_eq!(get(&sgb, 18, 0), 0);
+    assert_eq!(get(&sgb, 19, 0), 1);
+    assert_eq!(get(&sgb, 0, 1), 2);
+    assert_eq!(get(&sgb, 1, 1), 3);
+    assert_eq!(get(&sgb, 1, 3), 3); // cell 43
+    assert_eq!(get(&sgb, 2, 3), 2); // untouched
+    // and down a column
+    send(&mut sgb, &[0x07 << 3 | 1, 0, 17, 2, 0, 1, 0b0110_0000]);
+    assert_eq!(get(&sgb, 0, 17), 1);
+    assert_eq!(get(&sgb, 1, 0), 2);
+}
+
+#[test]
+fn multiplayer() {
+    let mut headless = Headless::with_color_mode(SGB_ROM, Some(ColorMode::Super));
+    assert_eq!(read(&headless, 0xFF00), 0xFF);
+    // MLT_REQ for 2 players, JOYP with no row selected gives the player
+    send_to_headless(&mut headless, &[0x11 << 3 | 1, 1]);
+    assert_eq!(read(&headless, 0xFF00), 0xFF);
+    write(&mut headless, 0xFF00, 0x10);
+    write(&mut headless, 0xFF00, 0x30);
+    assert_eq!(read(&headless, 0xFF00), 0xFE);
+    // player 2 has nothing plugged in
+    headless.emu.mbc.joypad.buttons = 0xFF; // everything held on player 1
+    write(&mut headless, 0xFF00, 0x10);
+    assert_eq!(read(&headless, 0xFF00) & 0x0F, 0x0F);
+    write(&mut headless, 0xFF00, 0x30);
+    assert_eq!(read(&headless, 0xFF00), 0xFF);
+    // back to 1 player
+    send_to_headless(&mut headless, &[0x11 << 3 | 1, 0]);
+    assert_eq!(headless.emu.mbc.sgb.as_ref().unwrap().players, 1);
+}
+
+fn get_pixel(screen: &[u8], x: usize, y: usize) -> [u8; 4] {
+    let idx = (y * SGB_WIDTH as usize + x) * 4;
+    screen[idx..idx + 4].try_into().unwrap()
+}
+
+// the game's shades, at (48,40) on the sgb screen, column x is shade x / 8 % 4
+fn make_game() -> Vec<u8> {
+    (0..160 * 144).map(|i| ((i % 160) / 8 % 4) as u8).collect()
+}
+
+#[test]
+fn draws_the_game_in_color() {
+    let mbc = Mbc::new();
+    let mut screen = vec![0u8; SGB_BUFFER_SIZE];
+    let mut sgb = Sgb::new(true);
+    send(&mut sgb, &PAL01);
+    // the bottom right cell uses palette 1
+    send(&mut sgb, &[0x07 << 3 | 1, 19, 17, 1, 0, 0, 0b01_000000]);
+    sgb.end_frame(&mbc, &make_game(), &mut screen);
+
+    // no border, the backdrop is color 0
+    assert_eq!(get_pixel(&screen, 0, 0), RED);
+    assert_eq!(get_pixel(&screen, 48, 40), RED);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), GREEN);
+    assert_eq!(get_pixel(&screen, 48 + 16, 40), BLUE);
+    assert_eq!(get_pixel(&screen, 48 + 24, 40), WHITE);
+    // cell 19 is shade 3
+    assert_eq!(get_pixel(&screen, 48 + 159, 40), WHITE);
+    assert_eq!(get_pixel(&screen, 48 + 159, 40 + 143), GREEN);
+
+    // MASK_EN: freeze keeps the frame it had
+    send(&mut sgb, &[0x17 << 3 | 1, 1]);
+    assert_eq!(sgb.mask, Mask::Freeze);
+    sgb.end_frame(&mbc, &make_game(), &mut screen);
+    sgb.end_frame(&mbc, &vec![0u8; 160 * 144], &mut screen);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), GREEN);
+    // black and color 0
+    send(&mut sgb, &[0x17 << 3 | 1, 2]);
+    sgb.end_frame(&mbc, &make_game(), &mut screen);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), BLACK);
+    assert_eq!(get_pixel(&screen, 0, 0), RED);
+    send(&mut sgb, &[0x17 << 3 | 1, 3]);
+    sgb.end_frame(&mbc, &make_game(), &mut screen);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), RED);
+    send(&mut sgb, &[0x17 << 3 | 1, 0]);
+    sgb.end_frame(&mbc, &make_game(), &mut screen);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), GREEN);
+}
+
+#[test]
+fn shades_go_through_the_dmg_palettes() {
+    let mut mbc = Mbc::new();
+    // BGP reversed, OBP0 all black, OBP1 shifted up one
+    mbc.hw_reg.bgp = 0b00_01_10_11;
+    mbc.hw_reg.obp0 = 0b11_11_11_11;
+    mbc.hw_reg.obp1 = 0b10_01_00_11;
+    let mut ppu = Ppu::new();
+    let buffer = Mutex::new(vec![0u8; 160 * 144 * 4]);
+    let mut buffer = buffer.lock().unwrap();
+    // color ids 0-3 as background, then as OBP0 and OBP1 sprites
+    for (is_sprite, palette) in [(false, 0), (true, 0), (true, 1)] {
+        for color_id in 0..4 {
+            let px = GBPixel { color: PaletteColor::from_u8(color_id), palette, ..GBPixel::new() };
+            let _ = ppu.push_pixel_and_advance_counter(&mbc, &mut buffer, px, is_sprite);
+        }
+    }
+    assert_eq!(ppu.shade_buffer[..12], [3, 2, 1, 0, 3, 3, 3, 3, 3, 0, 1, 2]);
+    // the dmg draws the same shades
+    for (i, &shade) in ppu.shade_buffer[..12].iter().enumerate() {
+        assert_eq!(buffer[i * 4..i * 4 + 4], SHADES[shade as usize], "pixel {}", i);
+    }
+
+    // and the sgb colors them, color id 0 of the background is palette 0's last color
+    let mut screen = vec![0u8; SGB_BUFFER_SIZE];
+    let mut sgb = Sgb::new(true);
+    send(&mut sgb, &PAL01);
+    sgb.end_frame(&mbc, &ppu.shade_buffer, &mut screen);
+    assert_eq!(get_pixel(&screen, 48, 40), WHITE);
+    assert_eq!(get_pixel(&screen, 48 + 3, 40), RED);
+    assert_eq!(get_pixel(&screen, 48 + 9, 40), RED);
+}
+
+#[test]
+fn border_transfer() {
+    // 0x8000 addressing with the map counting up 20 tiles a row, so the transfer is 0x8000-0x8FFF as is
+    let mut mbc = Mbc::new();
+    mbc.hw_reg.lcdc = 0x91;
+    for i in 0..256u16 {
+        mbc.write(0x9800 + (i / 20) * 32 + i % 20, i as u8, OpSource::Debugger);
+    }
+    let write_data = |mbc: &mut Mbc, data: &[u8]| {
+        for (i, &byte) in data.iter().enumerate() {
+            mbc.write(0x8000 + i as u16, byte, OpSource::Debugger);
+        }
+    };
+    let mut screen = vec![0u8; SGB_BUFFER_SIZE];
+    let game = make_game();
+    let mut sgb = Sgb::new(true);
+    send(&mut sgb, &PAL01);
+
+    // CHR_TRN 0: border tile 1 has its top left pixel in color 1 and top right in color 15
+    let mut data = vec![0u8; 0x1000];
+    data[32] = 0x81;
+    data[33] = 0x01;
+    data[32 + 16] = 0x01;
+    data[32 + 17] = 0x01;
+    write_data(&mut mbc, &data);
+    send(&mut sgb, &[0x13 << 3 | 1, 0]);
+    sgb.end_frame(&mbc, &game, &mut screen);
+    assert!(sgb.pending_transfer.is_none());
+    assert_eq!(sgb.border_tiles[32..34], [0x81, 0x01]);
+
+    // PCT_TRN: tile 1 at (0,0), x flipped at (1,0), y flipped at (2,0) with palette 5, and over the game at (6,5)
+    let mut data = vec![0u8; 0x1000];
+    for (entry, value) in [(0, 0x1001u16), (1, 0x5001), (2, 0x9401), (5 * 32 + 6, 0x1001)] {
+        data[entry * 2..entry * 2 + 2].copy_from_slice(&value.to_le_bytes());
+    }
+    // palette 4 color 1 blue and 15 white, palette 5 color 1 green
+    data[0x800 + 2..0x800 + 4].copy_from_slice(&0x7C00u16.to_le_bytes());
+    data[0x800 + 30..0x800 + 32].copy_from_slice(&0x7FFFu16.to_le_bytes());
+    data[0x820 + 2..0x820 + 4].copy_from_slice(&0x03E0u16.to_le_bytes());
+    write_data(&mut mbc, &data);
+    send(&mut sgb, &[0x14 << 3 | 1]);
+    sgb.end_frame(&mbc, &game, &mut screen);
+
+    assert_eq!(get_pixel(&screen, 0, 0), BLUE);
+    assert_eq!(get_pixel(&screen, 7, 0), WHITE);
+    assert_eq!(get_pixel(&screen, 1, 0), RED, "color 0 shows the backdrop");
+    assert_eq!(get_pixel(&screen, 0, 1), RED);
+    assert_eq!(get_pixel(&screen, 8, 0), WHITE);
+    assert_eq!(get_pixel(&screen, 15, 0), BLUE);
+    assert_eq!(get_pixel(&screen, 16, 7), GREEN);
+    assert_eq!(get_pixel(&screen, 16, 0), RED);
+    // border over the game, see through where it's color 0
+    assert_eq!(get_pixel(&screen, 48, 40), BLUE);
+    assert_eq!(get_pixel(&screen, 48 + 1, 40), RED);
+    assert_eq!(get_pixel(&screen, 48 + 8, 40), GREEN);
+}
+
+#[test]
+fn tamagotchi_loads_its_border() {
+    let mut headless = Headless::with_color_mode(SGB_ROM, Some(ColorMode::Super));
+    assert!(headless.run_frames(600));
+    assert!(headless.emu.cpu.locked_up.is_none());
+    let sgb = headless.emu.mbc.sgb.as_ref().unwrap();
+    // it checks for the sgb with MLT_REQ and goes back to 1 player
+    assert_eq!(sgb.players, 1);
+    assert!(sgb.border_tiles.iter().any(|&byte| byte != 0));
+    assert!(sgb.border_map.iter().any(|&entry| entry != 0));
+    assert_eq!(headless.copy_sgb_buffer().len(), SGB_BUFFER_SIZE);
+
+    // the sgb colors the shades the ppu kept, they're the greys it drew
+    // the ppu doesn't get to the last few pixels of a frame yet, only the drawn ones are compared
+    let game = headless.copy_game_buffer();
+    let drawn = game.chunks_exact(4).take_while(|px| px[3] != 0).count();
+    assert!(drawn > 20_000);
+    for (i, &shade) in headless.emu.ppu.shade_buffer[..drawn].iter().enumerate() {
+        assert_eq!(game[i * 4..i * 4 + 4], SHADES[shade as usize], "pixel {}", i);
+    }
+}
@@ -0,0 +1,62 @@
+// .sym files from rgbds and wlalink
+// cargo test --test symbols
+
+use gbemu::gb::symbols::Symbols;
+
+const SYM: &str = "\
+; File generated by rgblink
+00:0150 Start
+00:0150 Start.alias
+01:48fd memcpy ; copies bc bytes from hl to de
+01:4900 memcpy.loop
+0a:4000 BankedData
+
+not a label
+01:zzzz broken
+0150 no_bank
+02:4000
+[sections]
+00:0000 not_a_label
+[labels]
+ff:ff80 hram_routine
+";
+
+#[test]
+fn parse() {
+    let symbols = Symbols::parse(SYM);
+    assert_eq!(symbols.len(), 6);
+    assert_eq!(symbols.get_address("memcpy"), Some((0x01, 0x48FD)));
+    assert_eq!(symbols.get_address("BankedData"), Some((0x0A, 0x4000)));
+    assert_eq!(symbols.get_address("hram_routine"), Some((0xFF, 0xFF80)));
+    // broken lines and other sections are skipped
+    for name in ["broken", "no_bank", "not_a_label", "copies"] {
+        assert_eq!(symbols.get_address(name), None, "{}", name);
+    }
+    assert_eq!(symbols.get_label(0x02, 0x4000), None);
+}
+
+#[test]
+fn lookup() {
+    let symbols = Symbols::parse(SYM);
+    // the first label at an address is the one shown, the alias still works by name
+    assert_eq!(symbols.get_label(0x00, 0x0150), Some("Start"));
+    assert_eq!(symbols.get_address("Start.alias"), Some((0x00, 0x0150)));
+    // the same address in another bank is something else
+    assert_eq!(symbols.get_label(0x01, 0x4000), None);
+    assert_eq!(symbols.get_label(0x0A, 0x4000), Some("BankedData"));
+
+    assert_eq!(symbols.describe(0x01, 0x48FD).as_deref(), Some("memcpy"));
+    assert_eq!(symbols.describe(0x01, 0x48FF).as_deref(), Some("memcpy+2"));
+    assert_eq!(symbols.describe(0x01, 0x4905).as_deref(), Some("memcpy.loop+5"));
+    // nothing before it in the same bank
+    assert_eq!(symbols.describe(0x01, 0x4000), None);
+    assert_eq!(symbols.describe(0x0B, 0x4000), None);
+}
+
+#[test]
+fn empty() {
+    let symbols = Symbols::default();
+    assert!(symbols.is_empty());
+    assert_eq!(symbols.describe(0, 0x100), None);
+    assert!(Symbols::parse("; nothing here\n\n").is_empty());
+}
@@ -0,0 +1,59 @@
+// gameboy doctor traces, the start of a rom's log has to line up with the reference one
+// cargo test --test trace
+
+use std::fs;
+
+use gbemu::gb::bios::ColorMode;
+use gbemu::gb::headless::Headless;
+use gbemu::gb::trace::Tracer;
+
+const ROM: &str = "09-op-r-r.gb";
+// the first lines of gameboy doctor's reference log for blargg's 09-op r,r, the whole log is too big to keep around
+const REFERENCE_HEAD: &str = "tests/reference/09-op-r-r-doctor-head.log";
+
+// the reference logs start from a dmg's post boot rom registers with LY stuck at 0x90
+fn trace(name: &str, lines: u64) -> Vec<String> {
+    let file = std::env::temp_dir().join(format!("gbemu-trace-{}-{}.log", name, std::process::id()));
+    let file = file.to_str().unwrap();
+    let mut headless = Headless::with_color_mode(ROM, Some(ColorMode::Gray));
+    headless.emu.mbc.is_ly_stubbed = true;
+    headless.emu.cpu.tracer = Some(Tracer::new(file, 0x100, Some(lines)).unwrap());
+    assert!(headless.run_until(10_000_000, |emu| emu.cpu.tracer.as_ref().unwrap().lines == lines));
+    let text = fs::read_to_string(file).unwrap();
+    let _ = fs::remove_file(file);
+    text.lines().map(String::from).collect()
+}
+
+#[test]
+fn matches_gameboy_doctor() {
+    let reference = fs::read_to_string(REFERENCE_HEAD).unwrap();
+    let reference: Vec<&str> = reference.lines().collect();
+    let lines = trace("head", reference.len() as u64);
+    for (i, (line, expected)) in lines.iter().zip(&reference).enumerate() {
+        assert_eq!(line, expected, "line {}", i + 1);
+    }
+}
+
+#[test]
+fn ly_reads_0x90() {
+    // the rom waits for vblank with LDH A,(44) / CP 90 and gets out of the loop the first time
+    let lines = trace("ly", 17_000);
+    let ly_read = lines.iter().position(|line| line.ends_with("PCMEM:F0,44,FE,90")).expect("no LY read");
+    assert!(lines[ly_read + 1].starts_with("A:90 "), "{}", lines[ly_read + 1]);
+    assert!(lines[ly_read + 2].contains(" F:C0 "), "{}", lines[ly_read + 2]);
+    assert!(lines[ly_read + 3].contains(" PC:CA01 "), "{}", lines[ly_read + 3]);
+}
+
+#[test]
+fn shut_down_flushes() {
+    let file = std::env::temp_dir().join(format!("gbemu-trace-shutdown-{}.log", std::process::id()));
+    let file = file.to_str().unwrap();
+    let mut headless = Headless::with_color_mode(ROM, Some(ColorMode::Gray));
+    headless.emu.cpu.tracer = Some(Tracer::new(file, 0x100, None).unwrap());
+    // short of the 1000 lines that get flushed on their own
+    assert!(headless.run_until(10_000_000, |emu| emu.cpu.tracer.as_ref().unwrap().lines == 500));
+    headless.emu.shut_down();
+    let text = fs::read_to_string(file).unwrap();
+    let _ = fs::remove_file(file);
+    assert_eq!(text.lines().count(), 500);
+}